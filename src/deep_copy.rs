@@ -0,0 +1,395 @@
+//! Deep copies of foreign object graphs into owned Rust values.
+//!
+//! Foreign libraries commonly hand out structures that point to further
+//! foreign memory, such as `struct { char *name; size_t n; Item *items; }`.
+//! The [`EFDeepCopy`] trait follows such pointers through the runtime's
+//! [`AllocTracker`], validates every reachable value, and produces an owned
+//! Rust representation (using [`String`], [`Vec`] and [`Box`]).
+//!
+//! Implementations for structs can be generated with the
+//! [`efdeepcopy_struct!`](crate::efdeepcopy_struct) macro.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::branding::EFID;
+use crate::types::{AccessScope, AllocScope, AllocTracker, EFPtr, EFRef, EFType};
+
+pub trait EFDeepCopy: EFType + Sized + 'static {
+    type Owned;
+
+    /// Validate the value referenced by `r`, and produce an owned copy of it
+    /// and all foreign memory reachable from it.
+    ///
+    /// At most `depth_budget` pointers are followed in sequence, which bounds
+    /// the recursion on deeply nested or cyclic structures. Returns `None` if
+    /// any reachable value is invalid, cannot be upgraded, or the depth budget
+    /// is exhausted.
+    fn deep_copy<R: AllocTracker, ID: EFID>(
+        r: EFRef<'_, ID, Self>,
+        alloc_scope: &AllocScope<'_, R, ID>,
+        access_scope: &AccessScope<ID>,
+        depth_budget: usize,
+    ) -> Option<Self::Owned>;
+}
+
+impl<ID: EFID, T: EFDeepCopy> EFRef<'_, ID, T> {
+    /// See [`EFDeepCopy::deep_copy`].
+    pub fn deep_copy<R: AllocTracker>(
+        &self,
+        alloc_scope: &AllocScope<'_, R, ID>,
+        access_scope: &AccessScope<ID>,
+        depth_budget: usize,
+    ) -> Option<T::Owned> {
+        T::deep_copy(*self, alloc_scope, access_scope, depth_budget)
+    }
+}
+
+/// Copy the NUL-terminated, UTF-8 encoded string pointed to by `ptr`.
+///
+/// Every byte up to and including the NUL terminator must be accessible
+/// through the runtime's [`AllocTracker`]. Returns `None` for null pointers.
+pub fn deep_copy_cstr<R: AllocTracker, ID: EFID>(
+    ptr: EFPtr<u8>,
+    alloc_scope: &AllocScope<'_, R, ID>,
+    access_scope: &AccessScope<ID>,
+) -> Option<String> {
+    // We don't know the string's length ahead of time, so upgrade it byte by
+    // byte until we find the NUL terminator:
+    let mut len = 0;
+    loop {
+        let byte = EFPtr::<u8>::from(ptr.0.wrapping_add(len)).upgrade(alloc_scope)?;
+        if *byte.validate(access_scope)? == 0 {
+            break;
+        }
+        len += 1;
+    }
+
+    let bytes = ptr.upgrade_slice(len, alloc_scope)?;
+    Some(String::from(&*bytes.validate_as_str(access_scope)?))
+}
+
+/// Deep copy `len` elements starting at `ptr`.
+///
+/// A null pointer is accepted only if `len` is zero.
+pub fn deep_copy_slice<T: EFDeepCopy, R: AllocTracker, ID: EFID>(
+    ptr: EFPtr<T>,
+    len: usize,
+    alloc_scope: &AllocScope<'_, R, ID>,
+    access_scope: &AccessScope<ID>,
+    depth_budget: usize,
+) -> Option<Vec<T::Owned>> {
+    if len == 0 {
+        return Some(Vec::new());
+    }
+
+    if depth_budget == 0 {
+        return None;
+    }
+
+    ptr.upgrade_slice(len, alloc_scope)?
+        .iter()
+        .map(|elem| T::deep_copy(elem, alloc_scope, access_scope, depth_budget - 1))
+        .collect()
+}
+
+macro_rules! deep_copy_primitive {
+    ($($target:ty),* $(,)?) => {
+	$(
+	    impl EFDeepCopy for $target {
+		type Owned = $target;
+
+		fn deep_copy<R: AllocTracker, ID: EFID>(
+		    r: EFRef<'_, ID, Self>,
+		    _alloc_scope: &AllocScope<'_, R, ID>,
+		    access_scope: &AccessScope<ID>,
+		    _depth_budget: usize,
+		) -> Option<Self::Owned> {
+		    r.validate(access_scope).map(|val| *val)
+		}
+	    }
+	)*
+    };
+}
+
+deep_copy_primitive!(
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    bool,
+    (),
+);
+
+impl<const N: usize, T: EFDeepCopy> EFDeepCopy for [T; N] {
+    type Owned = [T::Owned; N];
+
+    fn deep_copy<R: AllocTracker, ID: EFID>(
+        r: EFRef<'_, ID, Self>,
+        alloc_scope: &AllocScope<'_, R, ID>,
+        access_scope: &AccessScope<ID>,
+        depth_budget: usize,
+    ) -> Option<Self::Owned> {
+        let elems: [Option<T::Owned>; N] = core::array::from_fn(|idx| {
+            // This cannot fail, as idx < N:
+            T::deep_copy(r.get(idx)?, alloc_scope, access_scope, depth_budget)
+        });
+
+        if elems.iter().any(|elem| elem.is_none()) {
+            None
+        } else {
+            Some(elems.map(|elem| elem.unwrap()))
+        }
+    }
+}
+
+/// Pointers are followed and their pointee copied into a [`Box`]. Null
+/// pointers are represented as `None`.
+impl<T: EFDeepCopy> EFDeepCopy for EFPtr<T> {
+    type Owned = Option<Box<T::Owned>>;
+
+    fn deep_copy<R: AllocTracker, ID: EFID>(
+        r: EFRef<'_, ID, Self>,
+        alloc_scope: &AllocScope<'_, R, ID>,
+        access_scope: &AccessScope<ID>,
+        depth_budget: usize,
+    ) -> Option<Self::Owned> {
+        let ptr = *r.validate(access_scope)?;
+        if ptr.0.is_null() {
+            return Some(None);
+        }
+
+        if depth_budget == 0 {
+            return None;
+        }
+
+        let pointee = ptr.upgrade(alloc_scope)?;
+        T::deep_copy(pointee, alloc_scope, access_scope, depth_budget - 1)
+            .map(|owned| Some(Box::new(owned)))
+    }
+}
+
+/// Implement [`EFDeepCopy`] for a foreign struct, producing an owned struct
+/// with identically named fields.
+///
+/// Every field of the owned struct must be listed, with one of the following
+/// kinds:
+///
+/// - `value`: the field's type implements [`EFDeepCopy`], and is copied
+///   recursively. Embedded [`EFPtr`]s become `Option<Box<_>>`.
+/// - `cstr`: the field is a pointer to a NUL-terminated, UTF-8 encoded string
+///   (such as an `EFPtr<u8>` or `*const c_char`), copied into an
+///   `Option<String>`. Null pointers are represented as `None`.
+/// - `slice(len_field)`: the field is a pointer to an array of `len_field`
+///   elements (an integer member of the same struct), copied into a `Vec<_>`.
+///
+/// The foreign struct must implement [`EFType`], and must not be packed.
+///
+/// Usage example:
+///
+/// ```
+/// use encapfn::efdeepcopy_struct;
+/// use encapfn::types::{EFPtr, EFType};
+///
+/// #[repr(C)]
+/// struct ForeignItem {
+///     id: u32,
+/// }
+///
+/// unsafe impl EFType for ForeignItem {
+///     unsafe fn validate(_t: *const Self) -> bool {
+///         true
+///     }
+/// }
+///
+/// struct Item {
+///     id: u32,
+/// }
+///
+/// efdeepcopy_struct!(ForeignItem => Item { id: value });
+///
+/// #[repr(C)]
+/// struct ForeignList {
+///     name: *const core::ffi::c_char,
+///     n: usize,
+///     items: EFPtr<ForeignItem>,
+/// }
+///
+/// unsafe impl EFType for ForeignList {
+///     unsafe fn validate(_t: *const Self) -> bool {
+///         true
+///     }
+/// }
+///
+/// struct List {
+///     name: Option<String>,
+///     n: usize,
+///     items: Vec<Item>,
+/// }
+///
+/// efdeepcopy_struct!(ForeignList => List {
+///     name: cstr,
+///     n: value,
+///     items: slice(n),
+/// });
+/// ```
+#[macro_export]
+macro_rules! efdeepcopy_struct {
+    ($foreign:ty => $owned:ident {
+	$( $field:ident : $kind:ident $( ( $len:ident ) )? ),* $(,)?
+    }) => {
+	impl $crate::deep_copy::EFDeepCopy for $foreign {
+	    type Owned = $owned;
+
+	    fn deep_copy<R: $crate::types::AllocTracker, ID: $crate::branding::EFID>(
+		r: $crate::types::EFRef<'_, ID, Self>,
+		alloc_scope: &$crate::types::AllocScope<'_, R, ID>,
+		access_scope: &$crate::types::AccessScope<ID>,
+		depth_budget: usize,
+	    ) -> Option<Self::Owned> {
+		// Validate the struct itself first, which also makes its pointer
+		// and length members accessible:
+		let val = r.validate(access_scope)?;
+
+		Some($owned {
+		    $(
+			$field: $crate::efdeepcopy_struct!(
+			    @field $kind $( ( $len ) )?,
+			    r, val, $field, alloc_scope, access_scope, depth_budget
+			),
+		    )*
+		})
+	    }
+	}
+    };
+
+    (@field value, $r:ident, $val:ident, $field:ident,
+     $alloc_scope:ident, $access_scope:ident, $depth_budget:ident) => {{
+	// Safety: the member is contained in the upgraded and validated
+	// struct, and hence accessible and well-aligned:
+	let field_ref = unsafe {
+	    $crate::types::EFPtr::from(::core::ptr::addr_of!((*$r.as_ptr().0).$field))
+		.upgrade_unchecked::<ID>($r.id_imprint())
+	};
+	$crate::deep_copy::EFDeepCopy::deep_copy(
+	    field_ref,
+	    $alloc_scope,
+	    $access_scope,
+	    $depth_budget,
+	)?
+    }};
+
+    (@field cstr, $r:ident, $val:ident, $field:ident,
+     $alloc_scope:ident, $access_scope:ident, $depth_budget:ident) => {{
+	let ptr = $crate::types::EFPtr::from($val.$field).cast::<u8>();
+	if ptr.0.is_null() {
+	    None
+	} else {
+	    Some($crate::deep_copy::deep_copy_cstr(
+		ptr,
+		$alloc_scope,
+		$access_scope,
+	    )?)
+	}
+    }};
+
+    (@field slice ( $len:ident ), $r:ident, $val:ident, $field:ident,
+     $alloc_scope:ident, $access_scope:ident, $depth_budget:ident) => {{
+	$crate::deep_copy::deep_copy_slice(
+	    $crate::types::EFPtr::from($val.$field),
+	    $val.$len as usize,
+	    $alloc_scope,
+	    $access_scope,
+	    $depth_budget,
+	)?
+    }};
+}
+
+#[test]
+fn test_deep_copy_struct() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::MockRtAllocChain;
+
+    #[repr(C)]
+    struct ForeignNode {
+        name: EFPtr<u8>,
+        n: u32,
+        values: EFPtr<u16>,
+        next: EFPtr<ForeignNode>,
+    }
+
+    unsafe impl EFType for ForeignNode {
+        unsafe fn validate(_t: *const Self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Node {
+        name: Option<String>,
+        values: Vec<u16>,
+        next: Option<Box<Node>>,
+    }
+
+    crate::efdeepcopy_struct!(ForeignNode => Node {
+        name: cstr,
+        values: slice(n),
+        next: value,
+    });
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let mut values: [u16; 3] = [1, 2, 3];
+        let mut tail = ForeignNode {
+            name: EFPtr::null(),
+            n: 0,
+            values: EFPtr::null(),
+            next: EFPtr::null(),
+        };
+        let mut head = ForeignNode {
+            name: EFPtr::from(c"head".as_ptr() as *const u8),
+            n: 3,
+            values: EFPtr::from(values.as_mut_ptr()),
+            next: EFPtr::from(&mut tail as *mut ForeignNode),
+        };
+
+        // Allow all upgrades, we only test the traversal here:
+        let alloc_scope = unsafe {
+            AllocScope::<'_, _, EFLifetimeBranding<'_>>::new(
+                MockRtAllocChain::Base(true),
+                brand.get_imprint(),
+            )
+        };
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+        let head_ref = EFPtr::from(&mut head as *mut ForeignNode)
+            .upgrade(&alloc_scope)
+            .unwrap();
+
+        assert_eq!(
+            head_ref.deep_copy(&alloc_scope, &access_scope, 4),
+            Some(Node {
+                name: Some(String::from("head")),
+                values: alloc::vec![1, 2, 3],
+                next: Some(Box::new(Node {
+                    name: None,
+                    values: Vec::new(),
+                    next: None,
+                })),
+            })
+        );
+
+        // Following any pointer requires a non-zero budget:
+        assert!(head_ref.deep_copy(&alloc_scope, &access_scope, 0).is_none());
+    })
+}
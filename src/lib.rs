@@ -4,9 +4,19 @@
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(any(feature = "alloc", doc))]
+extern crate alloc;
+
 pub mod abi;
 pub mod branding;
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "alloc")))]
+#[cfg(any(feature = "alloc", doc))]
+pub mod deep_copy;
+pub mod marshal;
 pub mod rt;
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "shadow_memory")))]
+#[cfg(any(feature = "shadow_memory", doc))]
+pub mod shadow;
 pub mod types;
 mod util;
 
@@ -17,6 +27,35 @@ pub enum EFError {
     AllocNoMem,
     AllocInvalidLayout,
     IDMismatch,
+    /// Foreign code caused a fault, such as a segmentation fault, by
+    /// receiving `signal` for an access to `addr` (or `0`, if unknown).
+    ForeignFault {
+        signal: i32,
+        addr: usize,
+    },
+    /// Foreign code attempted to perform a system call it is not allowed to.
+    ForeignSyscallDenied {
+        syscall: usize,
+    },
+    /// The runtime refuses to execute foreign code, as a previous invocation
+    /// failed and may have left it in an inconsistent state.
+    RuntimePoisoned,
+    /// Foreign code did not return within the time it was allowed to run.
+    Timeout,
+    /// The runtime does not support the requested operation.
+    Unsupported,
+    /// A foreign function requires an output buffer larger than the maximum
+    /// length the caller permits. Holds the length the function reported to
+    /// require, if it did.
+    OutBufTooLarge {
+        required: Option<usize>,
+    },
+    /// A foreign function reports having written `written` bytes into an
+    /// output buffer of only `len` bytes.
+    OutBufOverrun {
+        written: usize,
+        len: usize,
+    },
 }
 
 pub type EFResult<T> = Result<types::EFCopy<T>, EFError>;
@@ -0,0 +1,454 @@
+//! Marshalling of Rust values into foreign memory.
+//!
+//! The [`EFMarshal`] trait describes how a Rust value, potentially containing
+//! references (such as `&str`, `&[&str]`, or structs pointing to strings), is
+//! laid out in foreign memory. A value is marshalled into a single stacked
+//! allocation through
+//! [`EncapfnRt::write_stacked_marshalled`](crate::rt::EncapfnRt::write_stacked_marshalled):
+//! its _root_ objects are placed at the start of this allocation, followed by
+//! all data reachable through references. Interior pointers are fixed up to
+//! point into this allocation.
+//!
+//! For instance, marshalling a `[Option<&str>]` yields a NULL-terminated,
+//! argv-style `char **` array, followed by the strings it points to.
+
+use crate::types::EFPtr;
+
+/// A bump allocator over the tail of a marshalling allocation, used for data
+/// reachable from the root objects.
+pub struct EFMarshalArena {
+    cur: *mut u8,
+    end: *mut u8,
+}
+
+impl EFMarshalArena {
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes of `len` bytes.
+    pub unsafe fn new(ptr: *mut u8, len: usize) -> Self {
+        EFMarshalArena {
+            cur: ptr,
+            end: ptr.wrapping_add(len),
+        }
+    }
+
+    /// Reserve well-aligned, uninitialized space for `count` elements of `T`.
+    ///
+    /// Panics if the arena is exhausted, which indicates that an
+    /// [`EFMarshal::nested_size`] implementation under-reported its size.
+    pub fn alloc<T>(&mut self, count: usize) -> *mut T {
+        let start = self
+            .cur
+            .wrapping_add(self.cur.align_offset(core::mem::align_of::<T>()));
+        let end = core::mem::size_of::<T>()
+            .checked_mul(count)
+            .and_then(|size| (start as usize).checked_add(size));
+
+        match end {
+            Some(end) if end <= self.end as usize => {
+                self.cur = start.wrapping_add(end - start as usize);
+                start as *mut T
+            }
+            _ => panic!("EFMarshalArena exhausted, EFMarshal::nested_size is inaccurate"),
+        }
+    }
+}
+
+/// Upper bound of bytes required to place `count` elements of `T` into an
+/// [`EFMarshalArena`], including alignment padding.
+pub const fn arena_size_of<T>(count: usize) -> usize {
+    core::mem::size_of::<T>() * count + core::mem::align_of::<T>() - 1
+}
+
+/// Types which can be marshalled into foreign memory.
+///
+/// A value is represented by [`EFMarshal::root_len`] consecutive root objects
+/// of type [`EFMarshal::Foreign`], and up to [`EFMarshal::nested_size`] bytes
+/// of data reachable from them.
+///
+/// # Safety
+///
+/// [`EFMarshal::marshal_into`] must initialize all root objects, must only
+/// allocate nested data from the provided arena, and must not allocate more
+/// than [`EFMarshal::nested_size`] bytes from it.
+///
+/// Marshalling allocations are zeroed beforehand, so root objects may be
+/// initialized field by field, leaving padding bytes zeroed. However,
+/// implementations must not write values containing padding bytes as a
+/// whole, as this may leave the padding bytes uninitialized.
+pub unsafe trait EFMarshal {
+    type Foreign: 'static;
+
+    fn root_len(&self) -> usize;
+
+    fn nested_size(&self) -> usize;
+
+    /// # Safety
+    ///
+    /// `root` must be well-aligned and valid for writes of `root_len()`
+    /// consecutive `Foreign` objects.
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, arena: &mut EFMarshalArena);
+}
+
+/// Marker for [`EFMarshal`] types which are represented by exactly one root
+/// object, and can thus be elements of marshalled arrays and slices, or
+/// members of marshalled structs.
+///
+/// # Safety
+///
+/// [`EFMarshal::root_len`] must return `1` for every value.
+pub unsafe trait EFMarshalOne: EFMarshal {}
+
+macro_rules! marshal_primitive {
+    ($($target:ty),* $(,)?) => {
+	$(
+	    unsafe impl EFMarshal for $target {
+		type Foreign = $target;
+
+		fn root_len(&self) -> usize {
+		    1
+		}
+
+		fn nested_size(&self) -> usize {
+		    0
+		}
+
+		unsafe fn marshal_into(&self, root: *mut Self::Foreign, _arena: &mut EFMarshalArena) {
+		    root.write(*self)
+		}
+	    }
+
+	    unsafe impl EFMarshalOne for $target {}
+	)*
+    };
+}
+
+marshal_primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool);
+
+/// Foreign pointers are passed through as-is.
+unsafe impl<T: 'static> EFMarshal for EFPtr<T> {
+    type Foreign = EFPtr<T>;
+
+    fn root_len(&self) -> usize {
+        1
+    }
+
+    fn nested_size(&self) -> usize {
+        0
+    }
+
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, _arena: &mut EFMarshalArena) {
+        root.write(*self)
+    }
+}
+
+unsafe impl<T: 'static> EFMarshalOne for EFPtr<T> {}
+
+/// References are marshalled as pointers to the marshalled referent.
+unsafe impl<T: EFMarshal + ?Sized> EFMarshal for &T {
+    type Foreign = EFPtr<T::Foreign>;
+
+    fn root_len(&self) -> usize {
+        1
+    }
+
+    fn nested_size(&self) -> usize {
+        arena_size_of::<T::Foreign>((**self).root_len()) + (**self).nested_size()
+    }
+
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, arena: &mut EFMarshalArena) {
+        let referent_root = arena.alloc::<T::Foreign>((**self).root_len());
+        (**self).marshal_into(referent_root, arena);
+        root.write(EFPtr(referent_root))
+    }
+}
+
+unsafe impl<T: EFMarshal + ?Sized> EFMarshalOne for &T {}
+
+/// Optional references are marshalled as nullable pointers.
+unsafe impl<T: EFMarshal + ?Sized> EFMarshal for Option<&T> {
+    type Foreign = EFPtr<T::Foreign>;
+
+    fn root_len(&self) -> usize {
+        1
+    }
+
+    fn nested_size(&self) -> usize {
+        self.as_ref().map(EFMarshal::nested_size).unwrap_or(0)
+    }
+
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, arena: &mut EFMarshalArena) {
+        match self {
+            Some(r) => EFMarshal::marshal_into(r, root, arena),
+            None => root.write(EFPtr::null()),
+        }
+    }
+}
+
+unsafe impl<T: EFMarshal + ?Sized> EFMarshalOne for Option<&T> {}
+
+/// Strings are marshalled as NUL-terminated byte arrays. Interior NUL bytes
+/// are not escaped.
+unsafe impl EFMarshal for str {
+    type Foreign = u8;
+
+    fn root_len(&self) -> usize {
+        self.len() + 1
+    }
+
+    fn nested_size(&self) -> usize {
+        0
+    }
+
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, _arena: &mut EFMarshalArena) {
+        core::ptr::copy_nonoverlapping(self.as_ptr(), root, self.len());
+        root.add(self.len()).write(0);
+    }
+}
+
+/// Slices are marshalled as arrays of their elements' root objects.
+unsafe impl<T: EFMarshalOne> EFMarshal for [T] {
+    type Foreign = T::Foreign;
+
+    fn root_len(&self) -> usize {
+        self.len()
+    }
+
+    fn nested_size(&self) -> usize {
+        self.iter().map(|elem| elem.nested_size()).sum()
+    }
+
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, arena: &mut EFMarshalArena) {
+        for (idx, elem) in self.iter().enumerate() {
+            elem.marshal_into(root.add(idx), arena);
+        }
+    }
+}
+
+unsafe impl<const N: usize, T: EFMarshalOne> EFMarshal for [T; N] {
+    type Foreign = [T::Foreign; N];
+
+    fn root_len(&self) -> usize {
+        1
+    }
+
+    fn nested_size(&self) -> usize {
+        self[..].nested_size()
+    }
+
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, arena: &mut EFMarshalArena) {
+        self[..].marshal_into(root as *mut T::Foreign, arena)
+    }
+}
+
+unsafe impl<const N: usize, T: EFMarshalOne> EFMarshalOne for [T; N] {}
+
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "alloc")))]
+#[cfg(any(feature = "alloc", doc))]
+unsafe impl<T: EFMarshalOne> EFMarshal for alloc::vec::Vec<T> {
+    type Foreign = T::Foreign;
+
+    fn root_len(&self) -> usize {
+        self[..].root_len()
+    }
+
+    fn nested_size(&self) -> usize {
+        self[..].nested_size()
+    }
+
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, arena: &mut EFMarshalArena) {
+        self[..].marshal_into(root, arena)
+    }
+}
+
+#[cfg_attr(feature = "nightly", doc(cfg(feature = "alloc")))]
+#[cfg(any(feature = "alloc", doc))]
+unsafe impl EFMarshal for alloc::string::String {
+    type Foreign = u8;
+
+    fn root_len(&self) -> usize {
+        self.as_str().root_len()
+    }
+
+    fn nested_size(&self) -> usize {
+        self.as_str().nested_size()
+    }
+
+    unsafe fn marshal_into(&self, root: *mut Self::Foreign, arena: &mut EFMarshalArena) {
+        self.as_str().marshal_into(root, arena)
+    }
+}
+
+/// Implement [`EFMarshal`] for a Rust struct, marshalling it into a foreign
+/// struct with identically named fields.
+///
+/// Every field of the Rust struct must implement [`EFMarshalOne`], and the
+/// foreign struct's corresponding field must be of the field's
+/// [`EFMarshal::Foreign`] type. For instance, a `&'a str` field is marshalled
+/// into an `EFPtr<u8>`.
+///
+/// Fields are written individually. Padding bytes of the foreign struct, as
+/// well as any of its fields not listed in the invocation, are left zeroed.
+///
+/// Usage example:
+///
+/// ```
+/// use encapfn::efmarshal_struct;
+/// use encapfn::types::EFPtr;
+///
+/// struct Args<'a> {
+///     name: &'a str,
+///     flags: u32,
+///     mode: u32,
+/// }
+///
+/// #[repr(C)]
+/// struct ForeignArgs {
+///     name: EFPtr<u8>,
+///     flags: u32,
+///     mode: u32,
+/// }
+///
+/// efmarshal_struct!(impl<'a> Args<'a> => ForeignArgs { name, flags, mode });
+/// ```
+///
+/// Structs without lifetime parameters can omit the `impl<...>` prefix.
+#[macro_export]
+macro_rules! efmarshal_struct {
+    (impl < $( $lt:lifetime ),* > $rust:ty => $foreign:ty {
+	$( $field:ident ),* $(,)?
+    }) => {
+	unsafe impl < $( $lt ),* > $crate::marshal::EFMarshal for $rust {
+	    type Foreign = $foreign;
+
+	    fn root_len(&self) -> usize {
+		1
+	    }
+
+	    fn nested_size(&self) -> usize {
+		0 $( + $crate::marshal::EFMarshal::nested_size(&self.$field) )*
+	    }
+
+	    unsafe fn marshal_into(
+		&self,
+		root: *mut Self::Foreign,
+		arena: &mut $crate::marshal::EFMarshalArena,
+	    ) {
+		$(
+		    $crate::marshal::EFMarshal::marshal_into(
+			&self.$field,
+			::core::ptr::addr_of_mut!((*root).$field),
+			arena,
+		    );
+		)*
+	    }
+	}
+
+	unsafe impl < $( $lt ),* > $crate::marshal::EFMarshalOne for $rust {}
+    };
+
+    ($rust:ty => $foreign:ty { $( $field:ident ),* $(,)? }) => {
+	$crate::efmarshal_struct!(impl<> $rust => $foreign { $( $field ),* });
+    };
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_marshal_argv() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+    use crate::rt::EncapfnRt;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+
+        let argv: [Option<&str>; 3] = [Some("ls"), Some("-la"), None];
+        rt.write_stacked_marshalled(
+            &argv[..],
+            &mut alloc_scope,
+            &mut access_scope,
+            |argv_slice, alloc_scope, access_scope| {
+                assert_eq!(argv_slice.len(), 3);
+                assert!(argv_slice
+                    .get(2)
+                    .unwrap()
+                    .copy(access_scope)
+                    .validate()
+                    .unwrap()
+                    .0
+                    .is_null());
+
+                // Interior pointers refer into the tracked allocation:
+                let arg1 = argv_slice
+                    .get(1)
+                    .unwrap()
+                    .copy(access_scope)
+                    .validate()
+                    .unwrap();
+                let arg1_slice = arg1.upgrade_slice(4, alloc_scope).unwrap();
+                assert_eq!(&*arg1_slice.validate(access_scope).unwrap(), b"-la\0",);
+            },
+        )
+        .unwrap();
+    });
+}
+
+#[test]
+fn test_marshal_struct_padding() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::{MockRt, MockRtAllocError, MockRtAllocator};
+    use crate::rt::EncapfnRt;
+
+    // Hands out the same buffer for every allocation, filled with garbage:
+    struct BufferAllocator(*mut [u64; 4]);
+
+    impl MockRtAllocator for BufferAllocator {
+        unsafe fn with_alloc<R, F: FnOnce(*mut ()) -> R>(
+            &self,
+            layout: core::alloc::Layout,
+            f: F,
+        ) -> Result<R, MockRtAllocError> {
+            if layout.size() > core::mem::size_of::<[u64; 4]>() || layout.align() > 8 {
+                return Err(MockRtAllocError::InvalidLayout);
+            }
+            unsafe { *self.0 = [u64::MAX; 4] };
+            Ok(f(self.0 as *mut ()))
+        }
+    }
+
+    struct Padded {
+        tag: u8,
+        value: u32,
+    }
+
+    #[repr(C)]
+    struct ForeignPadded {
+        tag: u8,
+        value: u32,
+        unlisted: u64,
+    }
+
+    efmarshal_struct!(Padded => ForeignPadded { tag, value });
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let mut buffer = [0_u64; 4];
+        let buffer: *mut [u64; 4] = &mut buffer;
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, BufferAllocator(buffer), brand) };
+
+        rt.write_stacked_marshalled(
+            &Padded { tag: 1, value: 2 },
+            &mut alloc_scope,
+            &mut access_scope,
+            |_, _, _| {
+                assert_eq!(
+                    unsafe { *(buffer as *const [u8; 16]) },
+                    [1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+                );
+            },
+        )
+        .unwrap();
+    });
+}
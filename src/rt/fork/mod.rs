@@ -0,0 +1,1649 @@
+//! A runtime isolating foreign code in a forked worker process.
+//!
+//! The [`ForkRt`] forks a worker process when it is created. All of its
+//! stacked allocations live in a shared-memory arena, mapped into both the
+//! worker and this process, and its [`AllocTracker`] only ever considers
+//! these allocations valid. The worker thus can't modify any other memory of
+//! this process, and faults or memory corruption in foreign code don't affect
+//! it.
+//!
+//! Closures passed to [`EncapfnRt::execute`] are copied into the arena and
+//! run in the worker. Their results are copied back, and validated as per
+//! their [`EFType`] implementation. Callbacks invoked by foreign code are
+//! forwarded back to this process, where they run with access to its memory. Requests are
+//! exchanged over a Unix domain socket pair, while all data is passed through
+//! the arena.
+//!
+//! As the worker is forked from this process, it runs the same binary at the
+//! same addresses, and has a (stale) copy of all memory at the time it was
+//! created. State of foreign libraries, such as their global variables, is
+//! private to the worker, and persists across calls until the runtime is
+//! [reset](ForkRt::reset).
+//!
+//! Workers can further be restricted in the system calls they may perform,
+//! see [`seccomp`].
+//!
+//! # Limitations
+//!
+//! The `ForkRt` does not resolve symbols: [`EncapfnRt::lookup_symbol`] always
+//! returns `None`. Generated bindings which look up foreign functions through
+//! the runtime can thus not be used with it. Instead, foreign functions must
+//! be invoked from closures passed to [`EncapfnRt::execute`] or
+//! [`EncapfnRt::try_execute`], which can call any function linked into this
+//! binary, as the worker runs the same binary at the same addresses.
+
+use core::cell::Cell;
+use core::ffi::{c_int, c_short, c_void, CStr};
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicI32, AtomicPtr, Ordering};
+use core::time::Duration;
+use std::time::Instant;
+
+use crate::abi::GenericABI;
+use crate::branding::EFID;
+use crate::rt::{CallbackContext, CallbackReturn, EncapfnRt, ReleasePolicy};
+use crate::types::{AccessScope, AllocScope, AllocTracker, EFType};
+use crate::EFError;
+
+pub mod seccomp;
+
+use seccomp::ForkRtSeccompPolicy;
+
+// We don't depend on the `libc` crate, but link against the C library through
+// the standard library anyways. These constants are valid for Linux on most
+// architectures (notably x86, ARM and RISC-V):
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 0x01;
+const MAP_ANONYMOUS: c_int = 0x20;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+const AF_UNIX: c_int = 1;
+const SOCK_STREAM: c_int = 1;
+const MSG_NOSIGNAL: c_int = 0x4000;
+const EINTR: c_int = 4;
+const POLLIN: c_short = 0x001;
+const SIGKILL: c_int = 9;
+const RLIMIT_DATA: c_int = 2;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: i64,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    fn socketpair(domain: c_int, ty: c_int, protocol: c_int, sv: *mut c_int) -> c_int;
+    fn send(fd: c_int, buf: *const c_void, len: usize, flags: c_int) -> isize;
+    fn recv(fd: c_int, buf: *mut c_void, len: usize, flags: c_int) -> isize;
+    fn close(fd: c_int) -> c_int;
+    fn fork() -> c_int;
+    fn kill(pid: c_int, sig: c_int) -> c_int;
+    fn waitpid(pid: c_int, status: *mut c_int, options: c_int) -> c_int;
+    fn poll(fds: *mut PollFd, nfds: u64, timeout: c_int) -> c_int;
+    fn prlimit(
+        pid: c_int,
+        resource: c_int,
+        new_limit: *const RLimit,
+        old_limit: *mut RLimit,
+    ) -> c_int;
+    fn _exit(status: c_int) -> !;
+    fn __errno_location() -> *mut c_int;
+}
+
+#[repr(C)]
+struct RLimit {
+    rlim_cur: core::ffi::c_ulong,
+    rlim_max: core::ffi::c_ulong,
+}
+
+#[repr(C)]
+struct PollFd {
+    fd: c_int,
+    events: c_short,
+    revents: c_short,
+}
+
+// Messages sent to the worker:
+const MSG_CALL: u8 = 1;
+const MSG_CALLBACK_RETURN: u8 = 2;
+
+// Messages sent by the worker:
+const MSG_RETURN: u8 = 1;
+const MSG_CALLBACK: u8 = 2;
+
+/// Exit status of a worker process whose Rust code panicked.
+pub const FORK_RT_WORKER_PANIC_EXIT_STATUS: c_int = 101;
+
+/// Exit status of a worker process which performed a system call not allowed
+/// by its [`ForkRtSeccompPolicy`].
+pub const FORK_RT_WORKER_SYSCALL_DENIED_EXIT_STATUS: c_int = 102;
+
+/// Exit status of a worker process which failed to limit its memory usage to
+/// the runtime's [allocation quota](ForkRt::set_alloc_quota).
+pub const FORK_RT_WORKER_ALLOC_LIMIT_EXIT_STATUS: c_int = 103;
+
+// Size of the data segment of this process, including its heap and private
+// anonymous mappings, as accounted against `RLIMIT_DATA`:
+fn data_size() -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kib = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmData:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<usize>()
+        .ok()?;
+    kib.checked_mul(1024)
+}
+
+// Limit the data segment of the process `pid` (or this process, if `0`) to
+// `base` plus `quota` bytes, without exceeding its hard limit:
+fn limit_data_size(pid: c_int, base: usize, quota: usize) -> bool {
+    let mut limit = RLimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { prlimit(pid, RLIMIT_DATA, core::ptr::null(), &mut limit) } != 0 {
+        return false;
+    }
+
+    limit.rlim_cur = core::cmp::min(
+        base.saturating_add(quota) as core::ffi::c_ulong,
+        limit.rlim_max,
+    );
+    unsafe { prlimit(pid, RLIMIT_DATA, &limit, core::ptr::null_mut()) == 0 }
+}
+
+#[repr(C)]
+pub struct CallbackTrampolineFnReturn {
+    reg0: usize,
+    reg1: usize,
+}
+
+// Use 6 arguments, as that's how many are passed in registers on x86.
+type CallbackTrampolineFn =
+    unsafe extern "C" fn(usize, usize, usize, usize, usize, usize) -> CallbackTrampolineFnReturn;
+
+#[derive(Debug, Clone)]
+pub struct ForkRtCallbackContext {
+    pub arg_regs: [usize; 6],
+}
+
+impl CallbackContext for ForkRtCallbackContext {
+    fn get_argument_register(&self, reg: usize) -> Option<usize> {
+        self.arg_regs.get(reg).copied()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ForkRtCallbackReturn {
+    pub return_regs: [usize; 2],
+}
+
+impl CallbackReturn for ForkRtCallbackReturn {
+    fn set_return_register(&mut self, reg: usize, value: usize) -> bool {
+        if let Some(r) = self.return_regs.get_mut(reg) {
+            *r = value;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// Placed at the start of the arena, and used to pass the parameters of
+// requests between this process and the worker. Each side copies the
+// parameters out of this structure before sending any other request.
+#[repr(C)]
+struct ForkRtControl {
+    // Parameters of `MSG_CALL`:
+    call_fn: usize,
+    call_closure: usize,
+    call_result: usize,
+    // Parameters of `MSG_CALLBACK`:
+    callback_id: usize,
+    callback_args: [usize; 6],
+    // Parameters of `MSG_CALLBACK_RETURN`:
+    callback_ret: [usize; 2],
+    // System call which caused the worker to exit with
+    // `FORK_RT_WORKER_SYSCALL_DENIED_EXIT_STATUS`:
+    syscall_denied: usize,
+}
+
+// State of the worker process, set after it has been forked:
+static WORKER_CONTROL: AtomicPtr<ForkRtControl> = AtomicPtr::new(core::ptr::null_mut());
+static WORKER_FD: AtomicI32 = AtomicI32::new(-1);
+
+fn send_msg(fd: c_int, msg: u8) -> bool {
+    loop {
+        let res = unsafe { send(fd, &msg as *const u8 as *const c_void, 1, MSG_NOSIGNAL) };
+        if res == 1 {
+            return true;
+        } else if res < 0 && unsafe { *__errno_location() } == EINTR {
+            continue;
+        } else {
+            return false;
+        }
+    }
+}
+
+// Returns `None` once the other side has closed its socket:
+fn recv_msg(fd: c_int) -> Option<u8> {
+    let mut msg: u8 = 0;
+    loop {
+        let res = unsafe { recv(fd, &mut msg as *mut u8 as *mut c_void, 1, 0) };
+        if res == 1 {
+            return Some(msg);
+        } else if res < 0 && unsafe { *__errno_location() } == EINTR {
+            continue;
+        } else {
+            return None;
+        }
+    }
+}
+
+// Run in the worker on `MSG_CALL`, for a closure of type `F` placed at
+// `closure`:
+unsafe fn worker_call_closure<F: FnOnce() -> R, R>(closure: *mut u8, result: *mut u8) {
+    let f = unsafe { core::ptr::read(closure as *mut F) };
+    unsafe { core::ptr::write(result as *mut R, f()) };
+}
+
+fn worker_handle_call() {
+    let control = WORKER_CONTROL.load(Ordering::Relaxed);
+    let (call_fn, closure, result) = unsafe {
+        (
+            core::ptr::read_volatile(&(*control).call_fn),
+            core::ptr::read_volatile(&(*control).call_closure),
+            core::ptr::read_volatile(&(*control).call_result),
+        )
+    };
+
+    let call_fn: unsafe fn(*mut u8, *mut u8) = unsafe { core::mem::transmute(call_fn) };
+    unsafe { call_fn(closure as *mut u8, result as *mut u8) };
+
+    if !send_msg(WORKER_FD.load(Ordering::Relaxed), MSG_RETURN) {
+        unsafe { _exit(0) };
+    }
+}
+
+// Called from the `SIGSYS` handler installed along with the seccomp filter:
+#[cfg(target_arch = "x86_64")]
+fn worker_report_syscall_denied(syscall: usize) -> ! {
+    let control = WORKER_CONTROL.load(Ordering::Relaxed);
+    unsafe {
+        core::ptr::write_volatile(&mut (*control).syscall_denied, syscall);
+        _exit(FORK_RT_WORKER_SYSCALL_DENIED_EXIT_STATUS)
+    }
+}
+
+fn worker_main(
+    control: *mut ForkRtControl,
+    fd: c_int,
+    data_limit: Option<(usize, usize)>,
+    worker_init: Option<fn()>,
+    seccomp_policy: Option<&ForkRtSeccompPolicy>,
+) -> ! {
+    WORKER_CONTROL.store(control, Ordering::Relaxed);
+    WORKER_FD.store(fd, Ordering::Relaxed);
+
+    // Limit memory allocated by foreign code, including in `worker_init`:
+    if let Some((base, quota)) = data_limit {
+        if !limit_data_size(0, base, quota) {
+            unsafe { _exit(FORK_RT_WORKER_ALLOC_LIMIT_EXIT_STATUS) };
+        }
+    }
+
+    let res = std::panic::catch_unwind(|| {
+        if let Some(worker_init) = worker_init {
+            worker_init();
+        }
+
+        if let Some(seccomp_policy) = seccomp_policy {
+            assert!(
+                seccomp::install_filter(seccomp_policy),
+                "Failed to install seccomp filter in worker"
+            );
+        }
+
+        // Signal that the worker is ready to serve calls:
+        if !send_msg(fd, MSG_RETURN) {
+            return;
+        }
+
+        // Serve calls until the runtime closes its socket:
+        while let Some(msg) = recv_msg(fd) {
+            match msg {
+                MSG_CALL => worker_handle_call(),
+                _ => panic!("Unexpected message {} in worker", msg),
+            }
+        }
+    });
+
+    // Never return into, or run any destructors of the forked copy of this
+    // process' state:
+    unsafe {
+        _exit(if res.is_ok() {
+            0
+        } else {
+            FORK_RT_WORKER_PANIC_EXIT_STATUS
+        })
+    }
+}
+
+// Callback trampolines, run in the worker. Forwards the callback to the
+// runtime, and serves any calls made by the callback until it returns:
+extern "C" fn fork_rt_callback_trampoline<const CALLBACK_ID: usize>(
+    a0: usize,
+    a1: usize,
+    a2: usize,
+    a3: usize,
+    a4: usize,
+    a5: usize,
+) -> CallbackTrampolineFnReturn {
+    let control = WORKER_CONTROL.load(Ordering::Relaxed);
+    let fd = WORKER_FD.load(Ordering::Relaxed);
+
+    unsafe {
+        core::ptr::write_volatile(&mut (*control).callback_id, CALLBACK_ID);
+        core::ptr::write_volatile(&mut (*control).callback_args, [a0, a1, a2, a3, a4, a5]);
+    }
+
+    if !send_msg(fd, MSG_CALLBACK) {
+        unsafe { _exit(0) };
+    }
+
+    loop {
+        match recv_msg(fd) {
+            Some(MSG_CALL) => worker_handle_call(),
+            Some(MSG_CALLBACK_RETURN) => break,
+            _ => unsafe { _exit(0) },
+        }
+    }
+
+    let [reg0, reg1] = unsafe { core::ptr::read_volatile(&(*control).callback_ret) };
+    CallbackTrampolineFnReturn { reg0, reg1 }
+}
+
+// Trampolines indexed by callback ID, limiting the number of callbacks which
+// can be set up at any point in time:
+const FORK_RT_CALLBACKS: [CallbackTrampolineFn; 16] = [
+    fork_rt_callback_trampoline::<0>,
+    fork_rt_callback_trampoline::<1>,
+    fork_rt_callback_trampoline::<2>,
+    fork_rt_callback_trampoline::<3>,
+    fork_rt_callback_trampoline::<4>,
+    fork_rt_callback_trampoline::<5>,
+    fork_rt_callback_trampoline::<6>,
+    fork_rt_callback_trampoline::<7>,
+    fork_rt_callback_trampoline::<8>,
+    fork_rt_callback_trampoline::<9>,
+    fork_rt_callback_trampoline::<10>,
+    fork_rt_callback_trampoline::<11>,
+    fork_rt_callback_trampoline::<12>,
+    fork_rt_callback_trampoline::<13>,
+    fork_rt_callback_trampoline::<14>,
+    fork_rt_callback_trampoline::<15>,
+];
+
+/// A [`ForkRt`], along with the initial scopes to use with it.
+pub type ForkRtInstance<ID> = (
+    ForkRt<ID>,
+    AllocScope<'static, ForkRtAllocChain<'static>, ID>,
+    AccessScope<ID>,
+);
+
+#[derive(Clone, Copy, Debug)]
+struct ForkRtWorker {
+    pid: c_int,
+    fd: c_int,
+}
+
+pub struct ForkRt<ID: EFID> {
+    arena: *mut u8,
+    arena_len: usize,
+    // Address of the next free byte of the arena. Allocations are strictly
+    // nested, so we release them by resetting this to its previous value:
+    arena_top: Cell<usize>,
+    // Byte quota of allocations made through the `EncapfnRt` methods, and
+    // usage counters:
+    alloc_quota: usize,
+    alloc_used: Cell<usize>,
+    alloc_peak: Cell<usize>,
+    alloc_denied: Cell<usize>,
+    // Size of the data segment the current worker inherited from this
+    // process, if known:
+    worker_data_base: Cell<Option<usize>>,
+    release_policy: ReleasePolicy,
+    worker_init: Option<fn()>,
+    seccomp_policy: Option<ForkRtSeccompPolicy>,
+    // `None` once the worker has terminated:
+    worker: Cell<Option<ForkRtWorker>>,
+    // Point in time by which the current call must have returned, if any:
+    deadline: Cell<Option<Instant>>,
+    id_imprint: ID::Imprint,
+}
+
+impl<ID: EFID> ForkRt<ID> {
+    /// Create a new runtime with an arena of `arena_len` bytes, and fork its
+    /// worker process.
+    ///
+    /// `worker_init` is run in every worker process before it serves any
+    /// calls, and can be used to initialize foreign libraries. Afterwards, the
+    /// worker is restricted to the system calls allowed by `seccomp_policy`,
+    /// if provided. Seccomp filters are only supported on x86_64, and passing
+    /// a policy on other architectures returns [`EFError::Unsupported`].
+    ///
+    /// # Safety
+    ///
+    /// Closures passed to [`EncapfnRt::execute`] run in the worker process,
+    /// and must only capture values by copy (i.e., be `move` closures). Any
+    /// pointers they capture or return must point into this runtime's
+    /// allocations, as all other memory of the worker is a stale copy of this
+    /// process' memory, and vice versa. Values with destructors are rejected
+    /// at compile time, but references can't be detected.
+    ///
+    /// As with any use of `fork` without `exec`, code running in the worker
+    /// must not rely on locks held by other threads of this process at the
+    /// time the worker is forked.
+    pub unsafe fn new(
+        arena_len: usize,
+        worker_init: Option<fn()>,
+        seccomp_policy: Option<ForkRtSeccompPolicy>,
+        branding: ID,
+    ) -> Result<ForkRtInstance<ID>, EFError> {
+        #[cfg(not(target_arch = "x86_64"))]
+        if seccomp_policy.is_some() {
+            return Err(EFError::Unsupported);
+        }
+
+        let arena_len = arena_len
+            .checked_add(core::mem::size_of::<ForkRtControl>())
+            .ok_or(EFError::AllocInvalidLayout)?;
+
+        let arena = unsafe {
+            mmap(
+                core::ptr::null_mut(),
+                arena_len,
+                PROT_READ | PROT_WRITE,
+                MAP_SHARED | MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if arena == MAP_FAILED {
+            return Err(EFError::AllocNoMem);
+        }
+
+        let rt = ForkRt {
+            arena: arena as *mut u8,
+            arena_len,
+            arena_top: Cell::new(arena as usize + core::mem::size_of::<ForkRtControl>()),
+            alloc_quota: usize::MAX,
+            alloc_used: Cell::new(0),
+            alloc_peak: Cell::new(0),
+            alloc_denied: Cell::new(0),
+            worker_data_base: Cell::new(None),
+            release_policy: ReleasePolicy::Leave,
+            worker_init,
+            seccomp_policy,
+            worker: Cell::new(None),
+            deadline: Cell::new(None),
+            id_imprint: branding.get_imprint(),
+        };
+
+        // Dropping the runtime on failure releases the arena:
+        rt.spawn_worker()?;
+
+        Ok((
+            rt,
+            unsafe { AllocScope::new(ForkRtAllocChain::Base, branding.get_imprint()) },
+            unsafe { AccessScope::new(branding.get_imprint()) },
+        ))
+    }
+
+    fn control(&self) -> *mut ForkRtControl {
+        self.arena as *mut ForkRtControl
+    }
+
+    fn spawn_worker(&self) -> Result<(), EFError> {
+        let mut fds: [c_int; 2] = [-1; 2];
+        if unsafe { socketpair(AF_UNIX, SOCK_STREAM, 0, fds.as_mut_ptr()) } != 0 {
+            return Err(EFError::InternalError);
+        }
+
+        // The worker inherits the data segment of this process, so the quota
+        // only limits memory allocated beyond it:
+        let data_base = data_size();
+        self.worker_data_base.set(data_base);
+        let data_limit = match (data_base, self.alloc_quota) {
+            (_, usize::MAX) => None,
+            (Some(base), quota) => Some((base, quota)),
+            (None, _) => {
+                unsafe {
+                    close(fds[0]);
+                    close(fds[1]);
+                }
+                return Err(EFError::AllocNoMem);
+            }
+        };
+
+        let pid = unsafe { fork() };
+        if pid < 0 {
+            unsafe {
+                close(fds[0]);
+                close(fds[1]);
+            }
+            return Err(EFError::InternalError);
+        } else if pid == 0 {
+            unsafe { close(fds[0]) };
+            worker_main(
+                self.control(),
+                fds[1],
+                data_limit,
+                self.worker_init,
+                self.seccomp_policy.as_ref(),
+            );
+        }
+
+        unsafe { close(fds[1]) };
+        self.worker.set(Some(ForkRtWorker { pid, fd: fds[0] }));
+
+        // Wait for the worker to be initialized:
+        match recv_msg(fds[0]) {
+            Some(MSG_RETURN) => Ok(()),
+            _ => Err(self.terminate_worker()),
+        }
+    }
+
+    // Terminate the worker (if it is still running), and report how it
+    // terminated:
+    fn terminate_worker(&self) -> EFError {
+        let Some(worker) = self.worker.take() else {
+            return EFError::RuntimePoisoned;
+        };
+
+        let mut status: c_int = 0;
+        unsafe {
+            close(worker.fd);
+            kill(worker.pid, SIGKILL);
+            waitpid(worker.pid, &mut status, 0);
+        }
+
+        // Decode the wait status, as by `WIFSIGNALED`, `WTERMSIG` and
+        // `WEXITSTATUS`:
+        let signal = status & 0x7f;
+        if signal != 0 && signal != 0x7f {
+            EFError::ForeignFault { signal, addr: 0 }
+        } else if signal == 0 && (status >> 8) & 0xff == FORK_RT_WORKER_SYSCALL_DENIED_EXIT_STATUS {
+            EFError::ForeignSyscallDenied {
+                syscall: unsafe { core::ptr::read_volatile(&(*self.control()).syscall_denied) },
+            }
+        } else if signal == 0 && (status >> 8) & 0xff == FORK_RT_WORKER_ALLOC_LIMIT_EXIT_STATUS {
+            EFError::AllocNoMem
+        } else {
+            EFError::InternalError
+        }
+    }
+
+    // Receive a message from the worker, terminating it if the current call
+    // exceeds its deadline:
+    fn recv_worker_msg(&self, fd: c_int) -> Result<u8, EFError> {
+        if let Some(deadline) = self.deadline.get() {
+            loop {
+                // Round up, such that we don't wake up before the deadline:
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let timeout_ms = remaining.as_micros().div_ceil(1000).min(c_int::MAX as u128);
+
+                let mut pollfd = PollFd {
+                    fd,
+                    events: POLLIN,
+                    revents: 0,
+                };
+                let res = unsafe { poll(&mut pollfd, 1, timeout_ms as c_int) };
+
+                if res > 0 {
+                    break;
+                } else if res == 0 {
+                    self.terminate_worker();
+                    return Err(EFError::Timeout);
+                } else if unsafe { *__errno_location() } != EINTR {
+                    return Err(self.terminate_worker());
+                }
+            }
+        }
+
+        recv_msg(fd).ok_or_else(|| self.terminate_worker())
+    }
+
+    /// Whether the worker process has terminated, such as by a fault in
+    /// foreign code.
+    ///
+    /// When the worker terminates during a call to [`EncapfnRt::try_execute`],
+    /// it returns [`EFError::ForeignFault`] if the worker was terminated by a
+    /// signal, [`EFError::ForeignSyscallDenied`] if it violated its seccomp
+    /// policy, and [`EFError::InternalError`] otherwise. The worker is
+    /// terminated as well when a call made through
+    /// [`EncapfnRt::execute_with_timeout`] times out, and when a call returns
+    /// an invalid result or invokes an unknown callback, which is reported as
+    /// an [`EFError::InternalError`]. The runtime then refuses to execute
+    /// foreign code with [`EFError::RuntimePoisoned`] until it is
+    /// [reset](ForkRt::reset). [`EncapfnRt::execute`] panics in all of these
+    /// cases.
+    pub fn is_poisoned(&self) -> bool {
+        self.worker.get().is_none()
+    }
+
+    /// Terminate the worker process, and fork a new one.
+    ///
+    /// All state of foreign libraries is lost, and `worker_init` is run again
+    /// in the new worker.
+    pub fn reset(&mut self) -> Result<(), EFError> {
+        self.terminate_worker();
+        self.spawn_worker()
+    }
+
+    /// Number of bytes of the arena currently used by allocations.
+    pub fn arena_used(&self) -> usize {
+        self.arena_top.get() - self.arena as usize - core::mem::size_of::<ForkRtControl>()
+    }
+
+    /// Limit the number of bytes concurrently allocated through this
+    /// runtime's stacked allocation methods, and the memory foreign code in
+    /// the worker may allocate itself. Defaults to no limit beyond the size
+    /// of the arena.
+    ///
+    /// Stacked allocations which would exceed the quota fail with
+    /// [`EFError::AllocNoMem`]. The quota applies to the size of each
+    /// allocation as requested, and thus does not include alignment padding,
+    /// nor the runtime's internal use of the arena to pass closures to the
+    /// worker. Does not affect existing allocations, even if they exceed the
+    /// new quota.
+    ///
+    /// Separately, the worker's data segment (as limited by `RLIMIT_DATA`) may
+    /// grow by at most `quota` bytes beyond what it inherited from this
+    /// process. Allocations of foreign code beyond that fail as reported by
+    /// the allocator, such as by `malloc` returning `NULL`. Rust code in the
+    /// worker aborts instead, which is reported as an
+    /// [`EFError::ForeignFault`]. The limit applies to the running worker, and
+    /// all workers forked by [`ForkRt::reset`].
+    ///
+    /// Returns [`EFError::AllocNoMem`] if the limit can't be applied to the
+    /// running worker. The quota for stacked allocations is updated
+    /// regardless.
+    pub fn set_alloc_quota(&mut self, quota: usize) -> Result<(), EFError> {
+        self.alloc_quota = quota;
+
+        match (self.worker.get(), self.worker_data_base.get()) {
+            (None, _) => Ok(()),
+            (Some(worker), Some(base)) if limit_data_size(worker.pid, base, quota) => Ok(()),
+            (Some(_), _) => Err(EFError::AllocNoMem),
+        }
+    }
+
+    /// The number of bytes which may be allocated at any point in time.
+    pub fn alloc_quota(&self) -> usize {
+        self.alloc_quota
+    }
+
+    /// The number of bytes currently allocated, as accounted against the
+    /// quota.
+    pub fn alloc_used(&self) -> usize {
+        self.alloc_used.get()
+    }
+
+    /// The highest number of bytes allocated at any point in time, as
+    /// accounted against the quota.
+    pub fn alloc_peak(&self) -> usize {
+        self.alloc_peak.get()
+    }
+
+    /// The number of allocations which failed as they would have exceeded
+    /// the quota.
+    pub fn alloc_denied(&self) -> usize {
+        self.alloc_denied.get()
+    }
+
+    /// Set the policy applied to stacked allocations of this runtime once
+    /// their scope ends. Defaults to [`ReleasePolicy::Leave`].
+    ///
+    /// This also applies to the runtime's internal use of the arena to pass
+    /// closures and their results to the worker.
+    pub fn set_release_policy(&mut self, release_policy: ReleasePolicy) {
+        self.release_policy = release_policy;
+    }
+
+    // Allocate `layout` from the arena for the duration of `fun`. Allocations
+    // on behalf of the runtime itself are not accounted against the quota:
+    fn with_arena_alloc<F, R>(
+        &self,
+        layout: core::alloc::Layout,
+        internal: bool,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: FnOnce(*mut ()) -> R,
+    {
+        let used = if internal {
+            self.alloc_used.get()
+        } else {
+            match self.alloc_used.get().checked_add(layout.size()) {
+                Some(used) if used <= self.alloc_quota => used,
+                _ => {
+                    self.alloc_denied.set(self.alloc_denied.get() + 1);
+                    return Err(EFError::AllocNoMem);
+                }
+            }
+        };
+
+        let prev_top = self.arena_top.get();
+
+        let ptr = prev_top
+            .checked_next_multiple_of(layout.align())
+            .ok_or(EFError::AllocNoMem)?;
+        let top = ptr.checked_add(layout.size()).ok_or(EFError::AllocNoMem)?;
+        if top > self.arena as usize + self.arena_len {
+            return Err(EFError::AllocNoMem);
+        }
+
+        // Release the allocation even if `fun` unwinds, as the arena would be
+        // exhausted otherwise:
+        struct Release<'a> {
+            ptr: *mut u8,
+            len: usize,
+            release_policy: ReleasePolicy,
+            arena_top: &'a Cell<usize>,
+            prev_top: usize,
+            alloc_used: &'a Cell<usize>,
+            prev_used: usize,
+        }
+
+        impl Drop for Release<'_> {
+            fn drop(&mut self) {
+                unsafe { self.release_policy.apply(self.ptr, self.len) };
+                self.arena_top.set(self.prev_top);
+                self.alloc_used.set(self.prev_used);
+            }
+        }
+
+        let _release = Release {
+            ptr: ptr as *mut u8,
+            len: layout.size(),
+            release_policy: self.release_policy,
+            arena_top: &self.arena_top,
+            prev_top,
+            alloc_used: &self.alloc_used,
+            prev_used: self.alloc_used.replace(used),
+        };
+        self.alloc_peak
+            .set(core::cmp::max(self.alloc_peak.get(), used));
+        self.arena_top.set(top);
+
+        Ok(fun(ptr as *mut ()))
+    }
+
+    // Invoke a callback registered in `alloc_scope` on behalf of the worker.
+    // The callback ID is controlled by the worker, so we terminate it if no
+    // such callback is registered. How the worker terminates is then not
+    // meaningful, so we always report an `InternalError`:
+    fn dispatch_callback(
+        &self,
+        alloc_scope: &mut AllocScope<'_, ForkRtAllocChain<'_>, ID>,
+        access_scope: &mut AccessScope<ID>,
+    ) -> Result<(), EFError> {
+        let control = self.control();
+        let (callback_id, arg_regs) = unsafe {
+            (
+                core::ptr::read_volatile(&(*control).callback_id),
+                core::ptr::read_volatile(&(*control).callback_args),
+            )
+        };
+
+        let Some(callback_desc) = alloc_scope.tracker().find_callback_descriptor(callback_id)
+        else {
+            self.terminate_worker();
+            return Err(EFError::InternalError);
+        };
+
+        let mut callback_ret = ForkRtCallbackReturn {
+            return_regs: [0; 2],
+        };
+
+        let mut inner_alloc_scope: AllocScope<'_, ForkRtAllocChain<'_>, ID> = unsafe {
+            AllocScope::new(
+                ForkRtAllocChain::Cons(alloc_scope.tracker()),
+                alloc_scope.id_imprint(),
+            )
+        };
+
+        unsafe {
+            (callback_desc.wrapper)(
+                callback_desc.context,
+                &ForkRtCallbackContext { arg_regs },
+                &mut callback_ret,
+                &mut inner_alloc_scope as *mut _ as *mut (),
+                access_scope as *mut _ as *mut (),
+            )
+        };
+
+        unsafe {
+            core::ptr::write_volatile(&mut (*control).callback_ret, callback_ret.return_regs)
+        };
+
+        Ok(())
+    }
+}
+
+impl<ID: EFID> Drop for ForkRt<ID> {
+    fn drop(&mut self) {
+        self.terminate_worker();
+        unsafe { munmap(self.arena as *mut c_void, self.arena_len) };
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ForkRtAllocation {
+    ptr: *mut (),
+    len: usize,
+}
+
+impl ForkRtAllocation {
+    fn matches(&self, ptr: *mut (), len: usize) -> bool {
+        (ptr as usize) >= (self.ptr as usize)
+            && ((ptr as usize)
+                .checked_add(len)
+                .map(|end| end <= (self.ptr as usize) + self.len)
+                .unwrap_or(false))
+    }
+}
+
+#[derive(Debug)]
+pub struct ForkRtCallbackDescriptor<'a> {
+    wrapper: unsafe extern "C" fn(
+        *mut c_void,
+        &ForkRtCallbackContext,
+        &mut ForkRtCallbackReturn,
+        *mut (),
+        *mut (),
+    ),
+    context: *mut c_void,
+    _lt: PhantomData<&'a mut c_void>,
+}
+
+#[derive(Debug)]
+pub enum ForkRtAllocChain<'a> {
+    // In contrast to the MockRt, we never allow upgrading pointers outside of
+    // stacked allocations, as they don't refer to memory shared with the
+    // worker.
+    Base,
+    Allocation(ForkRtAllocation, &'a ForkRtAllocChain<'a>),
+    Callback(
+        usize,
+        ForkRtCallbackDescriptor<'a>,
+        &'a ForkRtAllocChain<'a>,
+    ),
+    Cons(&'a ForkRtAllocChain<'a>),
+}
+
+struct ForkRtAllocChainIter<'a>(Option<&'a ForkRtAllocChain<'a>>);
+
+impl<'a> Iterator for ForkRtAllocChainIter<'a> {
+    type Item = &'a ForkRtAllocChain<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(cur) = self.0 {
+            self.0 = match cur {
+                ForkRtAllocChain::Base => None,
+                ForkRtAllocChain::Allocation(_, pred) => Some(pred),
+                ForkRtAllocChain::Callback(_, _, pred) => Some(pred),
+                ForkRtAllocChain::Cons(pred) => Some(pred),
+            };
+
+            Some(cur)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> ForkRtAllocChain<'a> {
+    fn iter(&'a self) -> ForkRtAllocChainIter<'a> {
+        ForkRtAllocChainIter(Some(self))
+    }
+
+    fn is_valid_int(&self, ptr: *mut (), len: usize) -> bool {
+        self.iter().any(|elem| match elem {
+            ForkRtAllocChain::Allocation(alloc, _) => alloc.matches(ptr, len),
+            _ => false,
+        })
+    }
+
+    fn next_callback_id(&self) -> usize {
+        self.iter()
+            .find_map(|elem| match elem {
+                ForkRtAllocChain::Callback(id, _, _) => Some(id + 1),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    fn find_callback_descriptor(&self, id: usize) -> Option<&ForkRtCallbackDescriptor<'_>> {
+        self.iter().find_map(|elem| match elem {
+            ForkRtAllocChain::Callback(desc_id, desc, _) if id == *desc_id => Some(desc),
+            _ => None,
+        })
+    }
+}
+
+unsafe impl AllocTracker for ForkRtAllocChain<'_> {
+    fn is_valid(&self, ptr: *const (), len: usize) -> bool {
+        self.is_valid_int(ptr as *mut (), len)
+    }
+
+    fn is_valid_mut(&self, ptr: *mut (), len: usize) -> bool {
+        self.is_valid_int(ptr, len)
+    }
+}
+
+/// Closures passed to [`execute`](EncapfnRt::execute) and related methods
+/// are copied into the worker process, and their results copied back.
+/// Closures which capture or return values with destructors are rejected at
+/// compile time.
+///
+/// However, **these safe methods can produce dangling references**: a closure
+/// capturing a reference (e.g., a non-`move` closure), or returning one, is
+/// accepted, but any such reference points into memory private to the process
+/// it was created in. Only pointers into this runtime's allocations are
+/// meaningful in both processes. Upholding this is part of the safety contract
+/// of [`ForkRt::new`].
+unsafe impl<ID: EFID> EncapfnRt for ForkRt<ID> {
+    type ID = ID;
+    type AllocTracker<'a> = ForkRtAllocChain<'a>;
+    type ABI = GenericABI;
+    type CallbackTrampolineFn = CallbackTrampolineFn;
+    type CallbackContext = ForkRtCallbackContext;
+    type CallbackReturn = ForkRtCallbackReturn;
+
+    type SymbolTableState<const SYMTAB_SIZE: usize, const FIXED_OFFSET_SYMTAB_SIZE: usize> = ();
+
+    fn resolve_symbols<const SYMTAB_SIZE: usize, const FIXED_OFFSET_SYMTAB_SIZE: usize>(
+        &self,
+        _symbol_table: &'static [&'static CStr; SYMTAB_SIZE],
+        _fixed_offset_symbol_table: &'static [Option<&'static CStr>; FIXED_OFFSET_SYMTAB_SIZE],
+    ) -> Option<Self::SymbolTableState<SYMTAB_SIZE, FIXED_OFFSET_SYMTAB_SIZE>> {
+        Some(())
+    }
+
+    fn lookup_symbol<const SYMTAB_SIZE: usize, const FIXED_OFFSET_SYMTAB_SIZE: usize>(
+        &self,
+        _compact_symtab_index: usize,
+        _fixed_offset_symtab_index: usize,
+        _symtabstate: &Self::SymbolTableState<SYMTAB_SIZE, FIXED_OFFSET_SYMTAB_SIZE>,
+    ) -> Option<*const ()> {
+        // See the module documentation: foreign functions are called directly
+        // from closures passed to `execute`.
+        None
+    }
+
+    fn setup_callback<'a, C, F, R>(
+        &self,
+        callback: &'a mut C,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        C: FnMut(
+            &Self::CallbackContext,
+            &mut Self::CallbackReturn,
+            &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &mut AccessScope<Self::ID>,
+        ),
+        F: for<'b> FnOnce(
+            *const Self::CallbackTrampolineFn,
+            &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        ) -> R,
+    {
+        if self.id_imprint != alloc_scope.id_imprint() {
+            return Err(EFError::IDMismatch);
+        }
+
+        unsafe extern "C" fn callback_wrapper<
+            ID: EFID,
+            C: FnMut(
+                &ForkRtCallbackContext,
+                &mut ForkRtCallbackReturn,
+                &mut AllocScope<'_, ForkRtAllocChain<'_>, ID>,
+                &mut AccessScope<ID>,
+            ),
+        >(
+            ctx_ptr: *mut c_void,
+            callback_ctx: &ForkRtCallbackContext,
+            callback_ret: &mut ForkRtCallbackReturn,
+            alloc_scope: *mut (),
+            access_scope: *mut (),
+        ) {
+            let callback: &mut C = unsafe { &mut *(ctx_ptr as *mut C) };
+            let alloc_scope =
+                unsafe { &mut *(alloc_scope as *mut AllocScope<'_, ForkRtAllocChain<'_>, ID>) };
+            let access_scope = unsafe { &mut *(access_scope as *mut AccessScope<ID>) };
+
+            // For now, we assume that the function doesn't unwind:
+            callback(callback_ctx, callback_ret, alloc_scope, access_scope)
+        }
+
+        let callback_id = alloc_scope.tracker().next_callback_id();
+        let callback_trampoline = *FORK_RT_CALLBACKS
+            .get(callback_id)
+            .ok_or(EFError::InternalError)?;
+
+        let mut inner_alloc_scope = unsafe {
+            AllocScope::new(
+                ForkRtAllocChain::Callback(
+                    callback_id,
+                    ForkRtCallbackDescriptor {
+                        wrapper: callback_wrapper::<ID, C>,
+                        context: callback as *mut C as *mut c_void,
+                        _lt: PhantomData::<&'a mut c_void>,
+                    },
+                    alloc_scope.tracker(),
+                ),
+                alloc_scope.id_imprint(),
+            )
+        };
+
+        Ok(fun(
+            callback_trampoline as *const CallbackTrampolineFn,
+            &mut inner_alloc_scope,
+        ))
+    }
+
+    fn execute<R: EFType, F: FnOnce() -> R>(
+        &self,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        f: F,
+    ) -> R {
+        match self.try_execute(alloc_scope, access_scope, f) {
+            Ok(res) => res,
+            Err(err) => panic!("Failed to execute foreign code: {:?}", err),
+        }
+    }
+
+    fn try_execute<R: EFType, F: FnOnce() -> R>(
+        &self,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        f: F,
+    ) -> Result<R, EFError> {
+        if self.id_imprint != alloc_scope.id_imprint()
+            || self.id_imprint != access_scope.id_imprint()
+        {
+            panic!(
+                "ID mismatch! Rt: {:?}, AllocScope: {:?}, AccessScope: {:?}",
+                self.id_imprint,
+                alloc_scope.id_imprint(),
+                access_scope.id_imprint(),
+            );
+        }
+
+        // We can't transfer ownership of values that manage resources of
+        // either process:
+        const {
+            assert!(
+                !core::mem::needs_drop::<F>() && !core::mem::needs_drop::<R>(),
+                "Closures executed by the ForkRt must not capture or return values with destructors!"
+            )
+        };
+
+        let Some(worker) = self.worker.get() else {
+            return Err(EFError::RuntimePoisoned);
+        };
+
+        #[repr(C)]
+        struct CallSlots<F, R> {
+            closure: F,
+            result: core::mem::MaybeUninit<R>,
+        }
+
+        self.with_arena_alloc(core::alloc::Layout::new::<CallSlots<F, R>>(), true, |ptr| {
+            let slots = ptr as *mut CallSlots<F, R>;
+            let control = self.control();
+
+            unsafe {
+                core::ptr::write(core::ptr::addr_of_mut!((*slots).closure), f);
+                core::ptr::write_volatile(
+                    &mut (*control).call_fn,
+                    worker_call_closure::<F, R> as *const () as usize,
+                );
+                core::ptr::write_volatile(
+                    &mut (*control).call_closure,
+                    core::ptr::addr_of_mut!((*slots).closure) as usize,
+                );
+                core::ptr::write_volatile(
+                    &mut (*control).call_result,
+                    core::ptr::addr_of_mut!((*slots).result) as usize,
+                );
+            }
+
+            if !send_msg(worker.fd, MSG_CALL) {
+                return Err(self.terminate_worker());
+            }
+
+            // Serve callbacks until the call returns:
+            loop {
+                match self.recv_worker_msg(worker.fd)? {
+                    MSG_RETURN => break,
+                    MSG_CALLBACK => {
+                        self.dispatch_callback(alloc_scope, access_scope)?;
+
+                        // Calls made by the callback may have terminated the
+                        // worker, in which case its socket has been closed,
+                        // and the descriptor may since have been reused:
+                        if self.worker.get().is_none() {
+                            return Err(EFError::RuntimePoisoned);
+                        }
+
+                        if !send_msg(worker.fd, MSG_CALLBACK_RETURN) {
+                            return Err(self.terminate_worker());
+                        }
+                    }
+                    _ => return Err(self.terminate_worker()),
+                }
+            }
+
+            // The worker can write arbitrary bytes into the result slot. Copy
+            // the result out of the arena before validating it, such that it
+            // can't be modified afterwards:
+            let result: core::mem::MaybeUninit<R> =
+                unsafe { core::ptr::read_volatile(core::ptr::addr_of!((*slots).result)) };
+            if !R::ALWAYS_VALID && !unsafe { R::validate(result.as_ptr()) } {
+                self.terminate_worker();
+                return Err(EFError::InternalError);
+            }
+
+            Ok(unsafe { result.assume_init() })
+        })?
+    }
+
+    fn supports_timeout(&self) -> bool {
+        true
+    }
+
+    /// The timeout covers the entire call, including any callbacks into this
+    /// process and calls made by them. On timeout, the worker is terminated,
+    /// and the runtime must be [reset](ForkRt::reset).
+    fn execute_with_timeout<R: EFType, F: FnOnce() -> R>(
+        &self,
+        timeout: Duration,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        f: F,
+    ) -> Result<R, EFError> {
+        // Restore the deadline of any outer call, even if we panic:
+        struct RestoreDeadline<'a>(&'a Cell<Option<Instant>>, Option<Instant>);
+
+        impl Drop for RestoreDeadline<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.1);
+            }
+        }
+
+        let outer_deadline = self.deadline.get();
+        let _restore = RestoreDeadline(&self.deadline, outer_deadline);
+
+        // Timeouts which can't be represented are effectively infinite:
+        let deadline = Instant::now().checked_add(timeout);
+        self.deadline.set(match (outer_deadline, deadline) {
+            (Some(outer), Some(inner)) => Some(core::cmp::min(outer, inner)),
+            (outer, inner) => outer.or(inner),
+        });
+
+        self.try_execute(alloc_scope, access_scope, f)
+    }
+
+    fn allocate_stacked_untracked_mut<F, R>(
+        &self,
+        layout: core::alloc::Layout,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: FnOnce(*mut ()) -> R,
+    {
+        self.with_arena_alloc(layout, false, fun)
+    }
+
+    fn allocate_stacked_mut<F, R>(
+        &self,
+        layout: core::alloc::Layout,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(*mut (), &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>) -> R,
+    {
+        if self.id_imprint != alloc_scope.id_imprint() {
+            return Err(EFError::IDMismatch);
+        }
+
+        self.allocate_stacked_untracked_mut(layout, move |ptr| {
+            let mut inner_alloc_scope = unsafe {
+                AllocScope::new(
+                    ForkRtAllocChain::Allocation(
+                        ForkRtAllocation {
+                            ptr,
+                            len: layout.size(),
+                        },
+                        alloc_scope.tracker(),
+                    ),
+                    alloc_scope.id_imprint(),
+                )
+            };
+
+            fun(ptr, &mut inner_alloc_scope)
+        })
+    }
+}
+
+#[test]
+fn test_fork_rt_isolation() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::types::EFPtr;
+
+    // A library global, private to the worker:
+    static mut CALLS: u32 = 0;
+
+    extern "C" fn count_calls(out: *mut u32) {
+        unsafe {
+            CALLS += 1;
+            *out = CALLS;
+        }
+    }
+
+    extern "C" fn invoke_callback(cb: CallbackTrampolineFn, arg: usize) -> usize {
+        unsafe { cb(arg, 0, 0, 0, 0, 0) }.reg0
+    }
+
+    extern "C" fn bad_write(addr: usize) {
+        unsafe { core::ptr::write_volatile(addr as *mut u32, 42) };
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { ForkRt::new(4096, None, None, brand) }.unwrap();
+
+        // Library state persists across calls in the worker, and results are
+        // passed back through the arena:
+        rt.allocate_stacked_t_mut::<u32, _, _>(&mut alloc_scope, |out, alloc_scope| {
+            for i in 1..=2 {
+                let out_ptr: EFPtr<u32> = out.as_ptr();
+                rt.execute(alloc_scope, &mut access_scope, move || {
+                    count_calls(out_ptr.0)
+                });
+                assert_eq!(*out.validate(&access_scope).unwrap(), i);
+            }
+        })
+        .unwrap();
+        assert_eq!(unsafe { *core::ptr::addr_of!(CALLS) }, 0);
+
+        // Callbacks run in this process:
+        let mut received = 0;
+        let mut callback = |ctx: &ForkRtCallbackContext,
+                            ret: &mut ForkRtCallbackReturn,
+                            _alloc_scope: &mut AllocScope<'_, ForkRtAllocChain<'_>, _>,
+                            _access_scope: &mut AccessScope<_>| {
+            received = ctx.get_argument_register(0).unwrap();
+            ret.set_return_register(0, received + 1);
+        };
+        let res = rt
+            .setup_callback(&mut callback, &mut alloc_scope, |cb, alloc_scope| {
+                let cb: CallbackTrampolineFn = unsafe { core::mem::transmute(cb as *const ()) };
+                rt.execute(alloc_scope, &mut access_scope, move || {
+                    invoke_callback(cb, 41)
+                })
+            })
+            .unwrap();
+        assert_eq!((received, res), (41, 42));
+
+        // Faults terminate the worker, and poison the runtime until reset:
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || bad_write(8)),
+            Err(EFError::ForeignFault {
+                signal: 11,
+                addr: 0
+            })
+        );
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || 42),
+            Err(EFError::RuntimePoisoned)
+        );
+        rt.reset().unwrap();
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || 42),
+            Ok(42)
+        );
+        assert_eq!(rt.arena_used(), 0);
+    });
+}
+
+#[test]
+fn test_fork_rt_alloc_quota_foreign() {
+    use crate::branding::EFLifetimeBranding;
+
+    extern "C" {
+        fn malloc(size: usize) -> *mut c_void;
+        fn free(ptr: *mut c_void);
+    }
+
+    extern "C" fn try_malloc(size: usize) -> bool {
+        let ptr = unsafe { malloc(size) };
+        unsafe { free(ptr) };
+        !ptr.is_null()
+    }
+
+    const SIZE: usize = 64 << 20;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { ForkRt::new(4096, None, None, brand) }.unwrap();
+
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || try_malloc(SIZE)),
+            Ok(true)
+        );
+
+        // The quota applies to the running worker:
+        rt.set_alloc_quota(SIZE / 2).unwrap();
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || try_malloc(SIZE)),
+            Ok(false)
+        );
+
+        // ...and to workers forked afterwards:
+        rt.reset().unwrap();
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || try_malloc(SIZE)),
+            Ok(false)
+        );
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || try_malloc(SIZE / 4)),
+            Ok(true)
+        );
+
+        rt.set_alloc_quota(usize::MAX).unwrap();
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || try_malloc(SIZE)),
+            Ok(true)
+        );
+    });
+}
+
+#[test]
+fn test_fork_rt_release_policy() {
+    use crate::branding::EFLifetimeBranding;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { ForkRt::new(4096, None, None, brand) }.unwrap();
+        rt.set_release_policy(ReleasePolicy::Zeroize);
+
+        let mut addr = 0;
+        rt.write_stacked_slice_mut(
+            b"secret",
+            &mut alloc_scope,
+            &mut access_scope,
+            |secret, _, _| addr = secret.as_ptr().0 as usize,
+        )
+        .unwrap();
+        assert_eq!(unsafe { *(addr as *const [u8; 6]) }, [0; 6]);
+
+        // The policy is applied even if the closure panics:
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            rt.write_stacked_slice_mut(
+                b"secret",
+                &mut alloc_scope,
+                &mut access_scope,
+                |_, _, _| panic!(),
+            )
+        }));
+        assert!(res.is_err());
+        assert_eq!(unsafe { *(addr as *const [u8; 6]) }, [0; 6]);
+    });
+}
+
+#[test]
+fn test_fork_rt_invalid_result() {
+    use crate::branding::EFLifetimeBranding;
+
+    // A result type with a validity invariant not enforced by Rust:
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Even(u32);
+
+    unsafe impl EFType for Even {
+        unsafe fn validate(t: *const Self) -> bool {
+            unsafe { (*t).0.is_multiple_of(2) }
+        }
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { ForkRt::new(4096, None, None, brand) }.unwrap();
+
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || Even(2)),
+            Ok(Even(2))
+        );
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || Even(3)),
+            Err(EFError::InternalError)
+        );
+        assert!(rt.is_poisoned());
+    });
+}
+
+#[test]
+fn test_fork_rt_callback_failures() {
+    use crate::branding::EFLifetimeBranding;
+
+    extern "C" fn invoke_callback(cb: CallbackTrampolineFn) {
+        unsafe { cb(0, 0, 0, 0, 0, 0) };
+    }
+
+    extern "C" fn bad_write(addr: usize) {
+        unsafe { core::ptr::write_volatile(addr as *mut u32, 42) };
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { ForkRt::new(4096, None, None, brand) }.unwrap();
+
+        // A callback which isn't registered terminates the worker:
+        let unregistered = FORK_RT_CALLBACKS[5];
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, move || {
+                invoke_callback(unregistered)
+            }),
+            Err(EFError::InternalError)
+        );
+        assert!(rt.is_poisoned());
+        rt.reset().unwrap();
+
+        // A fault in a nested call made by a callback terminates the worker,
+        // and thus the outer call. The worker's socket descriptor is likely
+        // reused by the socket pair created afterwards, which must not be
+        // written to:
+        let mut nested_res = None;
+        let mut fds: [c_int; 2] = [-1; 2];
+        let mut callback = |_ctx: &ForkRtCallbackContext,
+                            _ret: &mut ForkRtCallbackReturn,
+                            alloc_scope: &mut AllocScope<'_, ForkRtAllocChain<'_>, _>,
+                            access_scope: &mut AccessScope<_>| {
+            nested_res = Some(rt.try_execute(alloc_scope, access_scope, || bad_write(8)));
+            assert_eq!(
+                unsafe { socketpair(AF_UNIX, SOCK_STREAM, 0, fds.as_mut_ptr()) },
+                0
+            );
+        };
+        let res = rt
+            .setup_callback(&mut callback, &mut alloc_scope, |cb, alloc_scope| {
+                let cb: CallbackTrampolineFn = unsafe { core::mem::transmute(cb as *const ()) };
+                rt.try_execute(alloc_scope, &mut access_scope, move || invoke_callback(cb))
+            })
+            .unwrap();
+        assert_eq!(res, Err(EFError::RuntimePoisoned));
+        assert_eq!(
+            nested_res,
+            Some(Err(EFError::ForeignFault {
+                signal: 11,
+                addr: 0
+            }))
+        );
+
+        const MSG_DONTWAIT: c_int = 0x40;
+        let mut buf = 0_u8;
+        assert_eq!(
+            unsafe { recv(fds[1], &mut buf as *mut u8 as *mut c_void, 1, MSG_DONTWAIT) },
+            -1
+        );
+        unsafe {
+            close(fds[0]);
+            close(fds[1]);
+        }
+        assert_eq!(rt.arena_used(), 0);
+    });
+}
+
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn test_fork_rt_seccomp() {
+    use crate::branding::EFLifetimeBranding;
+
+    extern "C" {
+        fn syscall(number: core::ffi::c_long, ...) -> core::ffi::c_long;
+    }
+
+    const SYS_GETPID: core::ffi::c_long = 39;
+
+    extern "C" fn get_pid() -> core::ffi::c_long {
+        unsafe { syscall(SYS_GETPID) }
+    }
+
+    extern "C" fn allocate() -> usize {
+        std::vec![0_u8; 1 << 20].len()
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { ForkRt::new(4096, None, Some(ForkRtSeccompPolicy::default()), brand) }
+                .unwrap();
+
+        // Memory management is allowed by default:
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || allocate()),
+            Ok(1 << 20)
+        );
+
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || get_pid()),
+            Err(EFError::ForeignSyscallDenied {
+                syscall: SYS_GETPID as usize
+            })
+        );
+    });
+}
+
+#[test]
+fn test_fork_rt_timeout() {
+    use crate::branding::EFLifetimeBranding;
+
+    extern "C" fn spin(iterations: usize) -> usize {
+        let mut i = 0;
+        while i < iterations {
+            i = core::hint::black_box(i + 1);
+        }
+        i
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { ForkRt::new(4096, None, None, brand) }.unwrap();
+        assert!(rt.supports_timeout());
+
+        assert_eq!(
+            rt.execute_with_timeout(
+                Duration::from_secs(10),
+                &mut alloc_scope,
+                &mut access_scope,
+                move || spin(1000)
+            ),
+            Ok(1000)
+        );
+
+        assert_eq!(
+            rt.execute_with_timeout(
+                Duration::from_millis(50),
+                &mut alloc_scope,
+                &mut access_scope,
+                move || spin(usize::MAX)
+            ),
+            Err(EFError::Timeout)
+        );
+        assert!(rt.is_poisoned());
+
+        rt.reset().unwrap();
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, move || spin(1)),
+            Ok(1)
+        );
+    });
+}
+
+#[test]
+fn test_fork_rt_alloc_quota() {
+    use crate::branding::EFLifetimeBranding;
+
+    extern "C" fn fill(out: *mut [u64; 2]) {
+        unsafe { *out = [1, 2] };
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { ForkRt::new(4096, None, None, brand) }.unwrap();
+        rt.set_alloc_quota(16).unwrap();
+
+        rt.allocate_stacked_t_mut::<[u64; 2], _, _>(&mut alloc_scope, |out, alloc_scope| {
+            assert_eq!(rt.alloc_used(), 16);
+            assert_eq!(
+                rt.allocate_stacked_t_mut::<u8, _, _>(alloc_scope, |_, _| ()),
+                Err(EFError::AllocNoMem)
+            );
+            assert_eq!(
+                rt.write_stacked_t(0_u8, alloc_scope, &mut access_scope, |_, _, _| ()),
+                Err(EFError::AllocNoMem)
+            );
+
+            // Passing the closure to the worker is not accounted against the
+            // quota:
+            let out_ptr = out.as_ptr();
+            rt.execute(alloc_scope, &mut access_scope, move || fill(out_ptr.0));
+            assert_eq!(*out.validate(&access_scope).unwrap(), [1, 2]);
+        })
+        .unwrap();
+
+        assert_eq!(rt.alloc_used(), 0);
+        assert_eq!(rt.alloc_peak(), 16);
+        assert_eq!(rt.alloc_denied(), 2);
+        assert_eq!(rt.arena_used(), 0);
+
+        // Allocations are released even if their closure panics:
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            rt.allocate_stacked_t_mut::<u64, _, _>(&mut alloc_scope, |_, _| panic!())
+        }));
+        assert!(res.is_err());
+        assert_eq!(rt.alloc_used(), 0);
+        assert_eq!(rt.arena_used(), 0);
+    });
+}
@@ -0,0 +1,271 @@
+//! seccomp-BPF restriction of [`ForkRt`](super::ForkRt) workers.
+//!
+//! Once the worker has run its `worker_init` function, it installs a filter
+//! only allowing the system calls of its [`ForkRtSeccompPolicy`]. Any other
+//! system call raises `SIGSYS` instead of being executed. The worker then
+//! records the offending system call in the arena and exits, which
+//! [`ForkRt`](super::ForkRt) reports as [`EFError::ForeignSyscallDenied`].
+//!
+//! System call numbers are architecture-specific. Filters are currently only
+//! supported on x86_64, and [`ForkRt::new`](super::ForkRt::new) returns
+//! [`EFError::Unsupported`] when passed a policy on other architectures.
+//!
+//! [`EFError::ForeignSyscallDenied`]: crate::EFError::ForeignSyscallDenied
+//! [`EFError::Unsupported`]: crate::EFError::Unsupported
+
+use core::ffi::c_long;
+
+// System call numbers on x86_64, as used by the default policy:
+#[cfg(target_arch = "x86_64")]
+mod syscalls {
+    use core::ffi::c_long;
+
+    pub const SYS_READ: c_long = 0;
+    pub const SYS_WRITE: c_long = 1;
+    pub const SYS_MMAP: c_long = 9;
+    pub const SYS_MPROTECT: c_long = 10;
+    pub const SYS_MUNMAP: c_long = 11;
+    pub const SYS_BRK: c_long = 12;
+    pub const SYS_RT_SIGPROCMASK: c_long = 14;
+    pub const SYS_RT_SIGRETURN: c_long = 15;
+    pub const SYS_SCHED_YIELD: c_long = 24;
+    pub const SYS_MREMAP: c_long = 25;
+    pub const SYS_MADVISE: c_long = 28;
+    pub const SYS_SENDTO: c_long = 44;
+    pub const SYS_RECVFROM: c_long = 45;
+    pub const SYS_EXIT: c_long = 60;
+    pub const SYS_FUTEX: c_long = 202;
+    pub const SYS_RESTART_SYSCALL: c_long = 219;
+    pub const SYS_EXIT_GROUP: c_long = 231;
+
+    // System calls required for the worker to serve calls and report
+    // violations, which are allowed regardless of the policy:
+    pub(super) const REQUIRED_SYSCALLS: [c_long; 5] = [
+        SYS_SENDTO,
+        SYS_RECVFROM,
+        SYS_RT_SIGRETURN,
+        SYS_EXIT,
+        SYS_EXIT_GROUP,
+    ];
+
+    // Memory management and synchronization system calls used by the Rust and
+    // C runtimes:
+    pub(super) const DEFAULT_SYSCALLS: [c_long; 10] = [
+        SYS_MMAP,
+        SYS_MPROTECT,
+        SYS_MUNMAP,
+        SYS_BRK,
+        SYS_MREMAP,
+        SYS_MADVISE,
+        SYS_FUTEX,
+        SYS_SCHED_YIELD,
+        SYS_RT_SIGPROCMASK,
+        SYS_RESTART_SYSCALL,
+    ];
+}
+
+#[cfg(target_arch = "x86_64")]
+pub use syscalls::*;
+
+// Filters are not supported on other architectures (see `ForkRt::new`):
+#[cfg(not(target_arch = "x86_64"))]
+const REQUIRED_SYSCALLS: [c_long; 0] = [];
+#[cfg(not(target_arch = "x86_64"))]
+const DEFAULT_SYSCALLS: [c_long; 0] = [];
+
+/// The set of system calls a [`ForkRt`](super::ForkRt) worker may perform
+/// after it has been initialized.
+///
+/// The system calls required for exchanging requests with the runtime are
+/// always allowed. By default, this policy additionally allows system calls
+/// for memory management and synchronization, but notably not `read` or
+/// `write`, nor any calls to open files or sockets.
+#[derive(Clone, Debug)]
+pub struct ForkRtSeccompPolicy {
+    syscalls: std::vec::Vec<c_long>,
+}
+
+impl ForkRtSeccompPolicy {
+    /// The default policy, allowing the system calls required for exchanging
+    /// requests with the runtime, and those for memory management and
+    /// synchronization.
+    pub fn new() -> Self {
+        ForkRtSeccompPolicy {
+            syscalls: REQUIRED_SYSCALLS
+                .iter()
+                .chain(DEFAULT_SYSCALLS.iter())
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// A policy allowing only the system calls required for exchanging
+    /// requests with the runtime.
+    pub fn minimal() -> Self {
+        ForkRtSeccompPolicy {
+            syscalls: REQUIRED_SYSCALLS.to_vec(),
+        }
+    }
+
+    /// Additionally allow the system call with number `syscall`.
+    pub fn allow(mut self, syscall: c_long) -> Self {
+        if !self.syscalls.contains(&syscall) {
+            self.syscalls.push(syscall);
+        }
+        self
+    }
+}
+
+impl Default for ForkRtSeccompPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod filter {
+    use core::ffi::{c_int, c_long, c_void};
+
+    const PR_SET_NO_NEW_PRIVS: c_int = 38;
+    const PR_SET_SECCOMP: c_int = 22;
+    const SECCOMP_MODE_FILTER: c_int = 2;
+    const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+    const SECCOMP_RET_TRAP: u32 = 0x0003_0000;
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+    const AUDIT_ARCH_X86_64: u32 = 0xc000_003e;
+
+    // Offsets into `struct seccomp_data`:
+    const SECCOMP_DATA_NR: u32 = 0;
+    const SECCOMP_DATA_ARCH: u32 = 4;
+
+    const BPF_LD_W_ABS: u16 = 0x20;
+    const BPF_JMP_JEQ_K: u16 = 0x15;
+    const BPF_RET_K: u16 = 0x06;
+
+    const SIGSYS: c_int = 31;
+    const SA_SIGINFO: c_int = 0x0000_0004;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct SockFilter {
+        code: u16,
+        jt: u8,
+        jf: u8,
+        k: u32,
+    }
+
+    #[repr(C)]
+    struct SockFprog {
+        len: u16,
+        filter: *const SockFilter,
+    }
+
+    #[repr(C)]
+    struct SigAction {
+        sa_sigaction: usize,
+        sa_mask: [u64; 16],
+        sa_flags: c_int,
+        sa_restorer: usize,
+    }
+
+    // The `_sigsys` member of `siginfo_t`:
+    #[repr(C)]
+    struct SigInfoSys {
+        si_signo: c_int,
+        si_errno: c_int,
+        si_code: c_int,
+        _pad: c_int,
+        call_addr: usize,
+        syscall: c_int,
+        arch: u32,
+    }
+
+    extern "C" {
+        fn prctl(option: c_int, ...) -> c_int;
+        fn sigaction(signum: c_int, act: *const SigAction, oldact: *mut SigAction) -> c_int;
+    }
+
+    const fn stmt(code: u16, k: u32) -> SockFilter {
+        SockFilter {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    unsafe extern "C" fn sigsys_handler(_signal: c_int, info: *mut SigInfoSys, _ctx: *mut c_void) {
+        unsafe { super::super::worker_report_syscall_denied((*info).syscall as usize) }
+    }
+
+    pub(super) fn install(syscalls: &[c_long]) -> bool {
+        // Each allowed system call jumps forward to the final `ALLOW`
+        // statement, which must be within reach of an 8-bit offset:
+        if syscalls.len() > u8::MAX as usize {
+            return false;
+        }
+
+        let mut program = std::vec![
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARCH),
+            SockFilter {
+                code: BPF_JMP_JEQ_K,
+                jt: 1,
+                jf: 0,
+                k: AUDIT_ARCH_X86_64,
+            },
+            stmt(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+            stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR),
+        ];
+        for (idx, syscall) in syscalls.iter().enumerate() {
+            program.push(SockFilter {
+                code: BPF_JMP_JEQ_K,
+                jt: (syscalls.len() - idx) as u8,
+                jf: 0,
+                k: *syscall as u32,
+            });
+        }
+        program.push(stmt(BPF_RET_K, SECCOMP_RET_TRAP));
+        program.push(stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+
+        let fprog = SockFprog {
+            len: program.len() as u16,
+            filter: program.as_ptr(),
+        };
+
+        let action = SigAction {
+            sa_sigaction: sigsys_handler as *const () as usize,
+            sa_mask: [0; 16],
+            sa_flags: SA_SIGINFO,
+            sa_restorer: 0,
+        };
+
+        unsafe {
+            sigaction(SIGSYS, &action, core::ptr::null_mut()) == 0
+                && prctl(
+                    PR_SET_NO_NEW_PRIVS,
+                    1 as c_long,
+                    0 as c_long,
+                    0 as c_long,
+                    0 as c_long,
+                ) == 0
+                && prctl(
+                    PR_SET_SECCOMP,
+                    SECCOMP_MODE_FILTER as c_long,
+                    &fprog as *const SockFprog as *const c_void,
+                ) == 0
+        }
+    }
+}
+
+/// Restrict the calling process to the system calls allowed by `policy`.
+/// Returns `false` if the filter could not be installed.
+pub(super) fn install_filter(policy: &ForkRtSeccompPolicy) -> bool {
+    #[cfg(target_arch = "x86_64")]
+    return filter::install(&policy.syscalls);
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = policy;
+        false
+    }
+}
@@ -0,0 +1,191 @@
+//! Declarations of multiple allocations placed into a single stacked frame.
+//!
+//! Instead of nesting one `allocate_stacked_*` closure per buffer, a tuple of
+//! [`EFStackedFrame`] declarations can be passed to
+//! [`EncapfnRt::allocate_stacked_frame_mut`](super::EncapfnRt::allocate_stacked_frame_mut).
+//! All declared allocations are laid out (with appropriate padding) in one
+//! stacked allocation, and handed to the closure as a tuple of
+//! [`EFMutRef`]s and [`EFMutSlice`]s. Tuples nest, so a frame may itself
+//! contain tuples of declarations.
+
+use core::alloc::Layout;
+use core::marker::PhantomData;
+
+use crate::branding::EFID;
+use crate::types::{EFMutRef, EFMutSlice, EFPtr};
+
+/// A declaration of one or more allocations within a stacked frame.
+///
+/// # Safety
+///
+/// [`EFStackedFrame::materialize`] must only create references to memory
+/// within the first `layout().size()` bytes of the provided pointer.
+pub unsafe trait EFStackedFrame {
+    type Allocations<'alloc, ID: EFID>;
+
+    /// Layout of this declaration, or `None` if it overflows.
+    fn layout(&self) -> Option<Layout>;
+
+    /// # Safety
+    ///
+    /// `ptr` must be aligned to, and valid for writes of the size of,
+    /// `self.layout()`, for the entire lifetime `'alloc`. No other references
+    /// to this memory may exist.
+    unsafe fn materialize<'alloc, ID: EFID>(
+        &self,
+        ptr: *mut u8,
+        id_imprint: ID::Imprint,
+    ) -> Self::Allocations<'alloc, ID>;
+}
+
+/// Declares an allocation of a single, uninitialized `T`.
+pub struct EFStackedT<T: 'static>(PhantomData<T>);
+
+impl<T: 'static> EFStackedT<T> {
+    pub fn new() -> Self {
+        EFStackedT(PhantomData)
+    }
+}
+
+impl<T: 'static> Default for EFStackedT<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<T: 'static> EFStackedFrame for EFStackedT<T> {
+    type Allocations<'alloc, ID: EFID> = EFMutRef<'alloc, ID, T>;
+
+    fn layout(&self) -> Option<Layout> {
+        Some(Layout::new::<T>())
+    }
+
+    unsafe fn materialize<'alloc, ID: EFID>(
+        &self,
+        ptr: *mut u8,
+        id_imprint: ID::Imprint,
+    ) -> Self::Allocations<'alloc, ID> {
+        EFPtr::from(ptr as *mut T).upgrade_unchecked_mut(id_imprint)
+    }
+}
+
+/// Declares an allocation of `len` consecutive, uninitialized `T`s.
+pub struct EFStackedSlice<T: 'static> {
+    len: usize,
+    _t: PhantomData<T>,
+}
+
+impl<T: 'static> EFStackedSlice<T> {
+    pub fn new(len: usize) -> Self {
+        EFStackedSlice {
+            len,
+            _t: PhantomData,
+        }
+    }
+}
+
+unsafe impl<T: 'static> EFStackedFrame for EFStackedSlice<T> {
+    type Allocations<'alloc, ID: EFID> = EFMutSlice<'alloc, ID, T>;
+
+    fn layout(&self) -> Option<Layout> {
+        Layout::array::<T>(self.len).ok()
+    }
+
+    unsafe fn materialize<'alloc, ID: EFID>(
+        &self,
+        ptr: *mut u8,
+        id_imprint: ID::Imprint,
+    ) -> Self::Allocations<'alloc, ID> {
+        EFPtr::from(ptr as *mut T).upgrade_unchecked_slice_mut(self.len, id_imprint)
+    }
+}
+
+macro_rules! stacked_frame_tuple {
+    ($($decl:ident $val:ident),+) => {
+	unsafe impl<$($decl: EFStackedFrame),+> EFStackedFrame for ($($decl,)+) {
+	    type Allocations<'alloc, ID: EFID> = ($($decl::Allocations<'alloc, ID>,)+);
+
+	    fn layout(&self) -> Option<Layout> {
+		let ($($val,)+) = self;
+		let layout = Layout::new::<()>();
+		$(
+		    let (layout, _) = layout.extend($val.layout()?).ok()?;
+		)+
+		Some(layout)
+	    }
+
+	    // The last extended layout is not needed:
+	    #[allow(unused_assignments)]
+	    unsafe fn materialize<'alloc, ID: EFID>(
+		&self,
+		ptr: *mut u8,
+		id_imprint: ID::Imprint,
+	    ) -> Self::Allocations<'alloc, ID> {
+		let ($($val,)+) = self;
+		let mut layout = Layout::new::<()>();
+		($(
+		    {
+			// Same computation as in `layout`, which must have
+			// succeeded for this frame to have been allocated:
+			let (extended, offset) = layout.extend($val.layout().unwrap()).unwrap();
+			layout = extended;
+			$val.materialize(ptr.add(offset), id_imprint)
+		    },
+		)+)
+	    }
+	}
+    };
+}
+
+stacked_frame_tuple!(A a);
+stacked_frame_tuple!(A a, B b);
+stacked_frame_tuple!(A a, B b, C c);
+stacked_frame_tuple!(A a, B b, C c, D d);
+stacked_frame_tuple!(A a, B b, C c, D d, E e);
+stacked_frame_tuple!(A a, B b, C c, D d, E e, F f);
+stacked_frame_tuple!(A a, B b, C c, D d, E e, F f, G g);
+stacked_frame_tuple!(A a, B b, C c, D d, E e, F f, G g, H h);
+
+#[cfg(feature = "std")]
+#[test]
+fn test_allocate_stacked_frame() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+    use crate::rt::EncapfnRt;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+
+        rt.allocate_stacked_frame_mut(
+            (
+                EFStackedT::<u8>::new(),
+                EFStackedSlice::<u32>::new(3),
+                (EFStackedT::<u64>::new(), EFStackedSlice::<u16>::new(0)),
+            ),
+            &mut alloc_scope,
+            |(flag, words, (wide, empty)), alloc_scope| {
+                flag.write(1, &mut access_scope);
+                words.write_from_iter([1, 2, 3].into_iter(), &access_scope);
+                wide.write(u64::MAX, &mut access_scope);
+                assert_eq!(empty.len(), 0);
+
+                // All allocations are tracked, well-aligned, and disjoint:
+                let flag_ptr = flag.as_ptr();
+                let words_ptr = words.as_ptr();
+                let wide_ptr = wide.as_ptr();
+                assert!(flag_ptr.upgrade_mut(alloc_scope).is_some());
+                assert!(words_ptr.upgrade_slice_mut(3, alloc_scope).is_some());
+                assert!(wide_ptr.upgrade_mut(alloc_scope).is_some());
+                assert!((flag_ptr.0 as usize) < (words_ptr.0 as usize));
+                assert!((words_ptr.0 as usize) + 12 <= (wide_ptr.0 as usize));
+
+                assert_eq!(*flag.validate(&access_scope).unwrap(), 1);
+                assert_eq!(&*words.validate(&access_scope).unwrap(), &[1, 2, 3]);
+                assert_eq!(*wide.validate(&access_scope).unwrap(), u64::MAX);
+            },
+        )
+        .unwrap();
+    });
+}
@@ -0,0 +1,313 @@
+//! Containment of faults in foreign code, see
+//! [`MockRt::set_contain_faults`](super::MockRt::set_contain_faults).
+//!
+//! We install a process-wide handler for synchronous fault signals, running
+//! on an alternate signal stack. Contained calls are made through a small
+//! assembly trampoline, which saves the stack pointer and a recovery address
+//! in a thread-local [`FaultRecovery`]. When a fault occurs on a thread with
+//! an active recovery point, the handler rewrites the interrupted context to
+//! return from the trampoline instead of resuming the faulting instruction.
+//! Faults on all other threads are forwarded to the previously installed
+//! handler.
+//!
+//! Resuming at the recovery point is akin to a `longjmp`, and skips over any
+//! Rust frames between the trampoline and the fault without running their
+//! cleanup. This is only sound if all of these frames are plain frames
+//! without destructors, which callers of
+//! [`MockRt::set_contain_faults`](super::MockRt::set_contain_faults) must
+//! ensure.
+
+use core::cell::Cell;
+use core::ffi::{c_int, c_void};
+use std::sync::OnceLock;
+
+use crate::EFError;
+
+// We don't depend on the `libc` crate, but link against the C library through
+// the standard library anyways. These constants and structure layouts are
+// valid for Linux on x86_64:
+const SIGBUS: c_int = 7;
+const SIGFPE: c_int = 8;
+const SIGSEGV: c_int = 11;
+const SA_SIGINFO: c_int = 0x0000_0004;
+const SA_ONSTACK: c_int = 0x0800_0000;
+const SS_DISABLE: c_int = 2;
+const SIG_DFL: usize = 0;
+const SIG_IGN: usize = 1;
+
+// Offsets into `ucontext_t::uc_mcontext.gregs`:
+const UCONTEXT_GREGS_OFFSET: usize = 40;
+const REG_RAX: usize = 13;
+const REG_RSP: usize = 15;
+const REG_RIP: usize = 16;
+
+/// Size of the alternate signal stack installed on threads without one.
+const FAULT_ALT_STACK_SIZE: usize = 64 * 1024;
+
+const CONTAINED_SIGNALS: [c_int; 3] = [SIGSEGV, SIGBUS, SIGFPE];
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SigAction {
+    sa_sigaction: usize,
+    sa_mask: [u64; 16],
+    sa_flags: c_int,
+    sa_restorer: usize,
+}
+
+#[repr(C)]
+struct StackT {
+    ss_sp: *mut c_void,
+    ss_flags: c_int,
+    ss_size: usize,
+}
+
+#[repr(C)]
+struct SigInfo {
+    si_signo: c_int,
+    si_errno: c_int,
+    si_code: c_int,
+    _pad: c_int,
+    si_addr: usize,
+}
+
+extern "C" {
+    fn sigaction(signum: c_int, act: *const SigAction, oldact: *mut SigAction) -> c_int;
+    fn sigaltstack(ss: *const StackT, old_ss: *mut StackT) -> c_int;
+
+    // Defined in assembly below. Calls `cb(data)` and returns 0, or returns
+    // 1 when the fault handler resumes execution at the recovery point:
+    fn encapfn_mock_rt_contained_call(
+        cb: unsafe extern "C" fn(*mut c_void),
+        data: *mut c_void,
+        recovery: *mut FaultRecovery,
+    ) -> u32;
+}
+
+core::arch::global_asm!(
+    ".pushsection .text.encapfn_mock_rt_contained_call,\"ax\",@progbits",
+    ".globl encapfn_mock_rt_contained_call",
+    ".type encapfn_mock_rt_contained_call,@function",
+    "encapfn_mock_rt_contained_call:",
+    // Save all callee-saved registers on the stack, such that we don't need
+    // to restore them in the fault handler. Together with the return address,
+    // this is 56 bytes, so realign the stack to 16 bytes for the call:
+    "push rbp",
+    "push rbx",
+    "push r12",
+    "push r13",
+    "push r14",
+    "push r15",
+    "sub rsp, 8",
+    // Store the stack pointer and recovery address into `recovery`:
+    "mov qword ptr [rdx], rsp",
+    "lea rax, [rip + .Lencapfn_mock_rt_contained_call_recover]",
+    "mov qword ptr [rdx + 8], rax",
+    // Invoke `cb(data)`:
+    "mov rax, rdi",
+    "mov rdi, rsi",
+    "call rax",
+    "xor eax, eax",
+    // The fault handler resumes execution here, with `rsp` restored and
+    // `eax` set to 1:
+    ".Lencapfn_mock_rt_contained_call_recover:",
+    "add rsp, 8",
+    "pop r15",
+    "pop r14",
+    "pop r13",
+    "pop r12",
+    "pop rbx",
+    "pop rbp",
+    "ret",
+    ".size encapfn_mock_rt_contained_call, . - encapfn_mock_rt_contained_call",
+    ".popsection",
+);
+
+#[repr(C)]
+struct FaultRecovery {
+    // Written by the trampoline:
+    sp: usize,
+    ip: usize,
+    // Written by the fault handler:
+    signal: c_int,
+    addr: usize,
+}
+
+std::thread_local! {
+    static ACTIVE_RECOVERY: Cell<*mut FaultRecovery> = const { Cell::new(core::ptr::null_mut()) };
+    static ALT_STACK_PRESENT: Cell<bool> = const { Cell::new(false) };
+}
+
+// Handlers installed before ours, to forward faults outside of contained
+// calls to:
+static PREV_ACTIONS: OnceLock<[(c_int, SigAction); CONTAINED_SIGNALS.len()]> = OnceLock::new();
+
+unsafe extern "C" fn fault_handler(signal: c_int, info: *mut SigInfo, ucontext: *mut c_void) {
+    let recovery = ACTIVE_RECOVERY
+        .try_with(|r| r.get())
+        .unwrap_or(core::ptr::null_mut());
+
+    if recovery.is_null() {
+        return unsafe { forward_fault(signal, info, ucontext) };
+    }
+
+    // Record the fault, and resume execution at the trampoline's recovery
+    // point once this handler returns:
+    unsafe {
+        (*recovery).signal = signal;
+        (*recovery).addr = (*info).si_addr;
+
+        let gregs = (ucontext as *mut u8).add(UCONTEXT_GREGS_OFFSET) as *mut usize;
+        *gregs.add(REG_RSP) = (*recovery).sp;
+        *gregs.add(REG_RIP) = (*recovery).ip;
+        *gregs.add(REG_RAX) = 1;
+    }
+}
+
+unsafe fn forward_fault(signal: c_int, info: *mut SigInfo, ucontext: *mut c_void) {
+    let prev = PREV_ACTIONS
+        .get()
+        .and_then(|actions| actions.iter().find(|(s, _)| *s == signal))
+        .map(|(_, action)| *action);
+
+    match prev {
+        Some(action) if action.sa_sigaction != SIG_DFL && action.sa_sigaction != SIG_IGN => {
+            if action.sa_flags & SA_SIGINFO != 0 {
+                let handler: unsafe extern "C" fn(c_int, *mut SigInfo, *mut c_void) =
+                    unsafe { core::mem::transmute(action.sa_sigaction) };
+                unsafe { handler(signal, info, ucontext) }
+            } else {
+                let handler: unsafe extern "C" fn(c_int) =
+                    unsafe { core::mem::transmute(action.sa_sigaction) };
+                unsafe { handler(signal) }
+            }
+        }
+        _ => {
+            // Restore the default disposition. Returning from this handler
+            // re-executes the faulting instruction, which then terminates the
+            // process as if we had never been installed:
+            let default = SigAction {
+                sa_sigaction: SIG_DFL,
+                sa_mask: [0; 16],
+                sa_flags: 0,
+                sa_restorer: 0,
+            };
+            unsafe { sigaction(signal, &default, core::ptr::null_mut()) };
+        }
+    }
+}
+
+/// Install the fault handler for all contained signals, if not installed
+/// already.
+pub(super) fn install_fault_handler() {
+    PREV_ACTIONS.get_or_init(|| {
+        let action = SigAction {
+            sa_sigaction: fault_handler as *const () as usize,
+            sa_mask: [0; 16],
+            sa_flags: SA_SIGINFO | SA_ONSTACK,
+            sa_restorer: 0,
+        };
+
+        CONTAINED_SIGNALS.map(|signal| {
+            let mut prev = SigAction {
+                sa_sigaction: SIG_DFL,
+                sa_mask: [0; 16],
+                sa_flags: 0,
+                sa_restorer: 0,
+            };
+            let res = unsafe { sigaction(signal, &action, &mut prev) };
+            assert!(res == 0, "Failed to install handler for signal {}", signal);
+            (signal, prev)
+        })
+    });
+}
+
+// The fault handler must run on an alternate stack, as faults may be caused
+// by foreign code exhausting or corrupting its stack. The standard library
+// installs one for all threads it creates. For other threads, we install an
+// alternate stack which is leaked when the thread exits.
+fn ensure_alt_stack() {
+    ALT_STACK_PRESENT.with(|present| {
+        if present.get() {
+            return;
+        }
+
+        let mut current = StackT {
+            ss_sp: core::ptr::null_mut(),
+            ss_flags: 0,
+            ss_size: 0,
+        };
+        unsafe { sigaltstack(core::ptr::null(), &mut current) };
+
+        if current.ss_flags & SS_DISABLE != 0 {
+            let stack = std::vec![0_u8; FAULT_ALT_STACK_SIZE].leak();
+            let alt_stack = StackT {
+                ss_sp: stack.as_mut_ptr() as *mut c_void,
+                ss_flags: 0,
+                ss_size: stack.len(),
+            };
+            let res = unsafe { sigaltstack(&alt_stack, core::ptr::null_mut()) };
+            assert!(res == 0, "Failed to install alternate signal stack");
+        }
+
+        present.set(true);
+    })
+}
+
+struct ContainedCall<F, R> {
+    f: Option<F>,
+    res: Option<std::thread::Result<R>>,
+}
+
+unsafe extern "C" fn contained_call_closure<F: FnOnce() -> R, R>(data: *mut c_void) {
+    let call = unsafe { &mut *(data as *mut ContainedCall<F, R>) };
+    let f = call.f.take().unwrap();
+
+    // Panics must not unwind through the trampoline, so catch them here and
+    // resume unwinding once we've returned from it:
+    call.res = Some(std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)));
+}
+
+/// Run `f`, returning [`EFError::ForeignFault`] if it causes a fault.
+///
+/// When a fault occurs, all frames of `f` and the foreign code it invokes are
+/// abandoned without unwinding them. This is undefined behavior unless they
+/// are plain frames without any cleanup, see the module documentation.
+/// Requires [`install_fault_handler`] to have been called.
+pub(super) fn contain_faults<F: FnOnce() -> R, R>(f: F) -> Result<R, EFError> {
+    ensure_alt_stack();
+
+    let mut call = ContainedCall {
+        f: Some(f),
+        res: None,
+    };
+    let mut recovery = FaultRecovery {
+        sp: 0,
+        ip: 0,
+        signal: 0,
+        addr: 0,
+    };
+
+    // Contained calls may be nested, for instance through callbacks:
+    let outer_recovery = ACTIVE_RECOVERY.with(|r| r.replace(&mut recovery));
+    let faulted = unsafe {
+        encapfn_mock_rt_contained_call(
+            contained_call_closure::<F, R>,
+            &mut call as *mut ContainedCall<F, R> as *mut c_void,
+            &mut recovery,
+        )
+    };
+    ACTIVE_RECOVERY.with(|r| r.set(outer_recovery));
+
+    if faulted != 0 {
+        return Err(EFError::ForeignFault {
+            signal: recovery.signal,
+            addr: recovery.addr,
+        });
+    }
+
+    match call.res.unwrap() {
+        Ok(res) => Ok(res),
+        Err(payload) => std::panic::resume_unwind(payload),
+    }
+}
@@ -0,0 +1,230 @@
+use core::ffi::{c_int, c_long, c_void};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+// We don't depend on the `libc` crate, but link against the C library through
+// the standard library anyways. These constants are valid for Linux on x86,
+// ARM and RISC-V, which this module is restricted to (see `super`):
+const PROT_NONE: c_int = 0;
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_PRIVATE: c_int = 0x02;
+const MAP_ANONYMOUS: c_int = 0x20;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+const SC_PAGESIZE: c_int = 30;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: c_long,
+    ) -> *mut c_void;
+    fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    fn sysconf(name: c_int) -> c_long;
+}
+
+/// An "electric fence" allocator, placing every allocation into its own
+/// mapping of pages, directly followed by an inaccessible guard page.
+///
+/// Foreign code writing past the end of an allocation thus faults
+/// immediately. Allocations are aligned to their layout's alignment, and
+/// hence may be followed by fewer than `align` bytes of accessible padding
+/// before the guard page. Accesses before the start of an allocation are only
+/// caught once they leave its mapping.
+pub struct GuardPageAllocator {
+    page_size: usize,
+    protect_released: bool,
+    live_allocations: AtomicUsize,
+}
+
+impl GuardPageAllocator {
+    pub fn new() -> Self {
+        GuardPageAllocator {
+            page_size: unsafe { sysconf(SC_PAGESIZE) } as usize,
+            protect_released: false,
+            live_allocations: AtomicUsize::new(0),
+        }
+    }
+
+    /// Instead of unmapping released allocations, make them inaccessible.
+    ///
+    /// This causes accesses to released allocations to fault, at the expense
+    /// of never releasing the address space used by allocations.
+    pub fn protect_released(mut self) -> Self {
+        self.protect_released = true;
+        self
+    }
+
+    /// Number of allocations which have been made, but not yet released.
+    pub fn live_allocations(&self) -> usize {
+        self.live_allocations.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for GuardPageAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl super::MockRtAllocator for GuardPageAllocator {
+    unsafe fn with_alloc<R, F: FnOnce(*mut ()) -> R>(
+        &self,
+        layout: core::alloc::Layout,
+        f: F,
+    ) -> Result<R, super::MockRtAllocError> {
+        // An allocation with an alignment larger than the page size can't be
+        // placed directly in front of the guard page:
+        if layout.align() > self.page_size {
+            return Err(super::MockRtAllocError::InvalidLayout);
+        }
+
+        let data_len = layout
+            .size()
+            .checked_next_multiple_of(self.page_size)
+            .ok_or(super::MockRtAllocError::InvalidLayout)?;
+        let mapping_len = data_len
+            .checked_add(self.page_size)
+            .ok_or(super::MockRtAllocError::InvalidLayout)?;
+
+        let mapping = unsafe {
+            mmap(
+                core::ptr::null_mut(),
+                mapping_len,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if mapping == MAP_FAILED {
+            return Err(super::MockRtAllocError::OutOfMemory);
+        }
+
+        // Release the mapping even if `f` unwinds:
+        struct Release<'a> {
+            mapping: *mut c_void,
+            len: usize,
+            protect: bool,
+            live_allocations: &'a AtomicUsize,
+        }
+
+        impl Drop for Release<'_> {
+            fn drop(&mut self) {
+                // There should not be any valid Rust references to this
+                // memory in scope any longer, as they must have been bound to
+                // the AllocScope with the anonymous lifetime as passed
+                // (reborrowed) into the closure:
+                if self.protect {
+                    unsafe { mprotect(self.mapping, self.len, PROT_NONE) };
+                } else {
+                    unsafe { munmap(self.mapping, self.len) };
+                }
+                self.live_allocations.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+
+        self.live_allocations.fetch_add(1, Ordering::Relaxed);
+        let mut release = Release {
+            mapping,
+            len: mapping_len,
+            protect: false,
+            live_allocations: &self.live_allocations,
+        };
+
+        let guard_page = unsafe { (mapping as *mut u8).add(data_len) };
+        if unsafe { mprotect(guard_page as *mut c_void, self.page_size, PROT_NONE) } != 0 {
+            return Err(super::MockRtAllocError::OutOfMemory);
+        }
+        release.protect = self.protect_released;
+
+        // Place the allocation as close to the guard page as its alignment
+        // permits:
+        let offset = (data_len - layout.size()) & !(layout.align() - 1);
+        Ok(f(unsafe { (mapping as *mut u8).add(offset) } as *mut ()))
+    }
+}
+
+#[test]
+fn test_guard_page_placement() {
+    use super::MockRtAllocator;
+
+    let allocator = GuardPageAllocator::new();
+    for (size, align) in [(1, 1), (13, 4), (4096, 8), (5000, 16), (0, 1)] {
+        let layout = core::alloc::Layout::from_size_align(size, align).unwrap();
+        let res = unsafe {
+            allocator.with_alloc(layout, |ptr| {
+                let ptr = ptr as *mut u8;
+                assert!((ptr as usize).is_multiple_of(align));
+
+                // The allocation must be fully accessible, and end within
+                // `align` bytes of the guard page:
+                core::ptr::write_bytes(ptr, 0xFF, size);
+                let end = ptr as usize + size;
+                assert!(end.next_multiple_of(allocator.page_size) - end < align);
+            })
+        };
+        assert!(res.is_ok());
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_guard_page_release_on_unwind() {
+    use super::MockRtAllocator;
+
+    let allocator = GuardPageAllocator::new();
+    let layout = core::alloc::Layout::from_size_align(16, 8).unwrap();
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+        allocator.with_alloc(layout, |_ptr| {
+            assert_eq!(allocator.live_allocations(), 1);
+            panic!("unwinding through with_alloc");
+        })
+    }));
+    assert!(res.is_err());
+
+    // The mapping must have been released:
+    assert_eq!(allocator.live_allocations(), 0);
+}
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+#[test]
+fn test_guard_page_overrun() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::MockRt;
+    use crate::rt::EncapfnRt;
+    use crate::EFError;
+
+    extern "C" fn bad_write(addr: usize) {
+        unsafe { core::ptr::write_volatile(addr as *mut u8, 42) };
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, GuardPageAllocator::new(), brand) };
+        // Safety: the closures below only invoke foreign functions:
+        unsafe { rt.set_contain_faults(true) };
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(13, &mut alloc_scope, |buf, alloc_scope| {
+            // Writing the last byte of the allocation succeeds, while writing
+            // one byte past its end hits the guard page:
+            let end = buf.as_ptr().0 as usize + 13;
+            assert_eq!(
+                rt.try_execute(alloc_scope, &mut access_scope, || bad_write(end - 1)),
+                Ok(())
+            );
+            assert_eq!(
+                rt.try_execute(alloc_scope, &mut access_scope, || bad_write(end)),
+                Err(EFError::ForeignFault {
+                    signal: 11,
+                    addr: end
+                })
+            );
+        })
+        .unwrap();
+    });
+}
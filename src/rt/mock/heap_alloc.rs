@@ -14,6 +14,9 @@ impl super::MockRtAllocator for HeapAllocator {
         // similar semantics by freeing allocations once we pop the current
         // stack frame:
         let ptr = unsafe { std::alloc::alloc(layout) };
+        if ptr.is_null() {
+            return Err(super::MockRtAllocError::OutOfMemory);
+        }
 
         // Execute the function:
         let ret = f(ptr as *mut ());
@@ -1,18 +1,58 @@
-use core::cell::UnsafeCell;
+use core::cell::{Cell, RefCell};
 use core::ffi::{c_void, CStr};
 use core::marker::PhantomData;
-use core::mem::MaybeUninit;
+use core::panic::Location;
 
 use crate::abi::GenericABI;
 use crate::branding::EFID;
-use crate::rt::{CallbackContext, CallbackReturn, EncapfnRt};
-use crate::types::{AccessScope, AllocScope, AllocTracker, EFMutRef, EFPtr, EFRef, EFSlice};
+use crate::rt::{CallbackContext, CallbackReturn, EncapfnRt, ReleasePolicy};
+use crate::types::{
+    AccessScope, AllocScope, AllocTracker, EFFromBytes, EFMutRef, EFMutSlice, EFPtr, EFRef,
+    EFSlice, EFType,
+};
 use crate::EFError;
 
 #[cfg_attr(feature = "nightly", doc(cfg(feature = "std")))]
 #[cfg(any(feature = "std", doc))]
 pub mod heap_alloc;
 
+#[cfg_attr(
+    feature = "nightly",
+    doc(cfg(all(
+        feature = "std",
+        target_os = "linux",
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv32",
+            target_arch = "riscv64"
+        )
+    )))
+)]
+#[cfg(any(
+    all(
+        feature = "std",
+        target_os = "linux",
+        any(
+            target_arch = "x86",
+            target_arch = "x86_64",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv32",
+            target_arch = "riscv64"
+        )
+    ),
+    doc
+))]
+pub mod guard_page_alloc;
+
+#[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+mod fault;
+
+pub mod quota_alloc;
+pub mod redzone_alloc;
 pub mod stack_alloc;
 
 // Use 6 arguments, as that's how many are passed in registers on x86.
@@ -161,18 +201,71 @@ impl<ID: EFID> MockRtCallbackTrampolinePool<ID> {
 
 pub enum MockRtAllocError {
     InvalidLayout,
+    OutOfMemory,
 }
 
 pub trait MockRtAllocator {
+    #[track_caller]
     unsafe fn with_alloc<R, F: FnOnce(*mut ()) -> R>(
         &self,
         layout: core::alloc::Layout,
         f: F,
     ) -> Result<R, MockRtAllocError>;
+
+    /// Allocate memory for the runtime's own bookkeeping, such as
+    /// shadow-memory bitmaps, which is never handed to foreign code.
+    ///
+    /// Allocator wrappers may exempt these allocations from their checks and
+    /// accounting. Defaults to [`with_alloc`](MockRtAllocator::with_alloc).
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`with_alloc`](MockRtAllocator::with_alloc).
+    #[track_caller]
+    unsafe fn with_internal_alloc<R, F: FnOnce(*mut ()) -> R>(
+        &self,
+        layout: core::alloc::Layout,
+        f: F,
+    ) -> Result<R, MockRtAllocError> {
+        unsafe { self.with_alloc(layout, f) }
+    }
+}
+
+/// Number of released allocations remembered for detecting retained
+/// pointers, see [`MockRt::set_check_retained_pointers`].
+pub const MOCK_RT_EXPIRED_ALLOCATIONS: usize = 64;
+
+/// Maximum number of regions scanned for retained pointers, see
+/// [`MockRt::add_retained_pointer_scan_region`].
+pub const MOCK_RT_RETAINED_POINTER_SCAN_REGIONS: usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+struct MockRtExpiredAllocation {
+    ptr: usize,
+    len: usize,
+    type_name: Option<&'static str>,
+    call_site: &'static Location<'static>,
+}
+
+struct MockRtRetainedPointerCheck {
+    // Ring buffer of the most recently released allocations:
+    expired: [Option<MockRtExpiredAllocation>; MOCK_RT_EXPIRED_ALLOCATIONS],
+    next_expired: usize,
+    scan_regions: [Option<(*const usize, usize)>; MOCK_RT_RETAINED_POINTER_SCAN_REGIONS],
 }
 
 pub struct MockRt<ID: EFID, A: MockRtAllocator> {
     zero_copy_immutable: bool,
+    check_immutable_allocations: bool,
+    check_retained_pointers: bool,
+    retained_pointer_check: RefCell<MockRtRetainedPointerCheck>,
+    release_policy: ReleasePolicy,
+    #[cfg(feature = "shadow_memory")]
+    shadow_memory: bool,
+    #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+    contain_faults: bool,
+    #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+    poisoned: Cell<bool>,
     allocator: A,
     id_imprint: ID::Imprint,
 }
@@ -191,6 +284,20 @@ impl<ID: EFID, A: MockRtAllocator> MockRt<ID, A> {
         (
             MockRt {
                 zero_copy_immutable,
+                check_immutable_allocations: false,
+                check_retained_pointers: false,
+                retained_pointer_check: RefCell::new(MockRtRetainedPointerCheck {
+                    expired: [None; MOCK_RT_EXPIRED_ALLOCATIONS],
+                    next_expired: 0,
+                    scan_regions: [None; MOCK_RT_RETAINED_POINTER_SCAN_REGIONS],
+                }),
+                release_policy: ReleasePolicy::Leave,
+                #[cfg(feature = "shadow_memory")]
+                shadow_memory: false,
+                #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+                contain_faults: false,
+                #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+                poisoned: Cell::new(false),
                 allocator,
                 id_imprint: branding.get_imprint(),
             },
@@ -204,6 +311,442 @@ impl<ID: EFID, A: MockRtAllocator> MockRt<ID, A> {
         )
     }
 
+    /// The underlying allocator of this runtime, such as to query the usage
+    /// counters of a [`QuotaAllocator`](quota_alloc::QuotaAllocator).
+    pub fn allocator(&self) -> &A {
+        &self.allocator
+    }
+
+    /// Enable or disable checking that foreign code does not modify
+    /// immutable allocations.
+    ///
+    /// When enabled, all immutable allocations tracked by the `AllocScope`
+    /// passed to [`EncapfnRt::execute`] are checksummed before running the
+    /// foreign code, and verified afterwards. `execute` panics with a message
+    /// naming the modified allocation when verification fails.
+    pub fn set_check_immutable_allocations(&mut self, check: bool) {
+        self.check_immutable_allocations = check;
+    }
+
+    /// Set the policy applied to stacked allocations of this runtime once
+    /// their scope ends. Defaults to [`ReleasePolicy::Leave`].
+    ///
+    /// This applies to all allocations made by this runtime, regardless of
+    /// the underlying allocator. Rust memory lent to foreign code is left
+    /// untouched.
+    pub fn set_release_policy(&mut self, release_policy: ReleasePolicy) {
+        self.release_policy = release_policy;
+    }
+
+    /// Enable or disable shadow-memory tracking of uninitialized bytes in
+    /// mutable stacked allocations.
+    ///
+    /// When enabled, these allocations are filled with
+    /// [`SHADOW_POISON`](crate::shadow::SHADOW_POISON) and registered with
+    /// [`crate::shadow`] for the duration of their scope. Validating or copying
+    /// bytes which were written neither through Rust, nor by foreign code then
+    /// panics. See [`crate::shadow`] for the limitations of this approach.
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "shadow_memory")))]
+    #[cfg(feature = "shadow_memory")]
+    pub fn set_shadow_memory(&mut self, shadow_memory: bool) {
+        self.shadow_memory = shadow_memory;
+    }
+
+    /// Enable or disable containment of faults caused by foreign code.
+    ///
+    /// When enabled, segmentation faults, bus errors and floating-point
+    /// exceptions raised while running foreign code in
+    /// [`EncapfnRt::try_execute`] make it return [`EFError::ForeignFault`],
+    /// instead of terminating the process. This installs a process-wide
+    /// signal handler, which forwards faults outside of such calls to the
+    /// previously installed handler.
+    ///
+    /// This is a debugging aid, and not suitable for production use. On a
+    /// fault, execution resumes in `try_execute` as if by `longjmp`: all
+    /// frames between it and the faulting instruction are abandoned without
+    /// unwinding them. These include Rust frames of the closure passed to
+    /// `try_execute`, and skipping their destructors and other cleanup is
+    /// undefined behavior unless they are plain frames without any.
+    ///
+    /// The runtime is poisoned after a fault, and refuses to execute foreign
+    /// code with [`EFError::RuntimePoisoned`] until [`MockRt::clear_poison`]
+    /// is called. Note that [`EncapfnRt::execute`], which can't fail
+    /// otherwise, panics in both cases once containment is enabled.
+    ///
+    /// # Safety
+    ///
+    /// While containment is enabled, closures passed to `try_execute` and
+    /// `execute` must not own values with destructors or hold locks across
+    /// calls into foreign code, and foreign code must not call back into Rust
+    /// code that does. Even so, values reachable from these closures may be
+    /// left in an inconsistent state after a fault.
+    #[cfg_attr(
+        feature = "nightly",
+        doc(cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64")))
+    )]
+    #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+    pub unsafe fn set_contain_faults(&mut self, contain_faults: bool) {
+        if contain_faults {
+            fault::install_fault_handler();
+        }
+        self.contain_faults = contain_faults;
+    }
+
+    /// Whether foreign code has caused a fault since this runtime was
+    /// created, or [`MockRt::clear_poison`] was last called.
+    #[cfg_attr(
+        feature = "nightly",
+        doc(cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64")))
+    )]
+    #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.get()
+    }
+
+    /// Allow executing foreign code again after it caused a fault.
+    ///
+    /// The caller is responsible for ensuring that the foreign library's state
+    /// is consistent, for instance by re-initializing it.
+    #[cfg_attr(
+        feature = "nightly",
+        doc(cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64")))
+    )]
+    #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+    pub fn clear_poison(&mut self) {
+        self.poisoned.set(false);
+    }
+
+    /// Enable or disable detecting foreign code retaining pointers to
+    /// released stacked allocations.
+    ///
+    /// When enabled, this runtime remembers the last
+    /// [`MOCK_RT_EXPIRED_ALLOCATIONS`] released allocations. After running
+    /// foreign code in [`EncapfnRt::execute`], all regions registered through
+    /// [`MockRt::add_retained_pointer_scan_region`] are scanned for
+    /// pointer-sized values pointing into these allocations. `execute` panics
+    /// with a message naming the leaked allocation when such a pointer is
+    /// found, unless it points into an allocation that is currently valid.
+    pub fn set_check_retained_pointers(&mut self, check: bool) {
+        self.check_retained_pointers = check;
+    }
+
+    /// Register a region of foreign memory (such as a library's global
+    /// variables) to scan for retained pointers to released allocations.
+    ///
+    /// Returns `false` if [`MOCK_RT_RETAINED_POINTER_SCAN_REGIONS`] regions
+    /// are already registered.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads of `len` bytes for as long as this
+    /// runtime exists.
+    pub unsafe fn add_retained_pointer_scan_region(&mut self, ptr: *const (), len: usize) -> bool {
+        // Only scan well-aligned, pointer-sized values:
+        let align_offset = ptr.align_offset(core::mem::align_of::<usize>());
+        let region = (
+            ptr.wrapping_byte_add(align_offset) as *const usize,
+            len.saturating_sub(align_offset) / core::mem::size_of::<usize>(),
+        );
+
+        match self
+            .retained_pointer_check
+            .get_mut()
+            .scan_regions
+            .iter_mut()
+            .find(|r| r.is_none())
+        {
+            Some(slot) => {
+                *slot = Some(region);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Track the initialization state of the `len` bytes at `ptr` for the
+    // duration of the closure, if shadow memory is enabled. The bitmap is
+    // an internal allocation of the underlying allocator:
+    #[track_caller]
+    fn with_shadow_region<F, R>(&self, _ptr: *mut (), _len: usize, fun: F) -> Result<R, EFError>
+    where
+        F: FnOnce() -> R,
+    {
+        #[cfg(feature = "shadow_memory")]
+        if self.shadow_memory && _len != 0 {
+            let layout = core::alloc::Layout::array::<u8>(crate::shadow::bitmap_len(_len))
+                .map_err(|_| EFError::AllocInvalidLayout)?;
+
+            let fun = move |bitmap: *mut ()| {
+                // If all shadow regions are in use, this allocation simply
+                // remains untracked:
+                let registered = unsafe {
+                    crate::shadow::register_region(_ptr as *mut u8, _len, bitmap as *mut u8)
+                };
+                let ret = fun();
+                if registered {
+                    crate::shadow::unregister_region(_ptr as *mut u8);
+                }
+                ret
+            };
+
+            return (unsafe { self.allocator.with_internal_alloc(layout, fun) }).map_err(
+                |e| match e {
+                    MockRtAllocError::InvalidLayout => EFError::AllocInvalidLayout,
+                    MockRtAllocError::OutOfMemory => EFError::AllocNoMem,
+                },
+            );
+        }
+
+        Ok(fun())
+    }
+
+    // Called once the scope of an allocation ends, before it is released:
+    unsafe fn release_allocation(
+        &self,
+        ptr: *mut u8,
+        len: usize,
+        type_name: Option<&'static str>,
+        call_site: &'static Location<'static>,
+    ) {
+        unsafe { self.release_policy.apply(ptr, len) };
+
+        if self.check_retained_pointers && len != 0 {
+            let mut check = self.retained_pointer_check.borrow_mut();
+            let idx = check.next_expired;
+            check.expired[idx] = Some(MockRtExpiredAllocation {
+                ptr: ptr as usize,
+                len,
+                type_name,
+                call_site,
+            });
+            check.next_expired = (idx + 1) % MOCK_RT_EXPIRED_ALLOCATIONS;
+        }
+    }
+
+    // Allocate `layout` through the underlying allocator, applying the release
+    // policy before the allocation is released, even if `fun` unwinds:
+    #[track_caller]
+    fn with_alloc_int<F, R>(
+        &self,
+        layout: core::alloc::Layout,
+        type_name: Option<&'static str>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: FnOnce(*mut ()) -> R,
+    {
+        struct Release<'a, ID: EFID, A: MockRtAllocator> {
+            rt: &'a MockRt<ID, A>,
+            ptr: *mut u8,
+            len: usize,
+            type_name: Option<&'static str>,
+            call_site: &'static Location<'static>,
+        }
+
+        impl<ID: EFID, A: MockRtAllocator> Drop for Release<'_, ID, A> {
+            fn drop(&mut self) {
+                unsafe {
+                    self.rt
+                        .release_allocation(self.ptr, self.len, self.type_name, self.call_site)
+                };
+            }
+        }
+
+        let call_site = Location::caller();
+        let fun = move |ptr: *mut ()| {
+            let _release = Release {
+                rt: self,
+                ptr: ptr as *mut u8,
+                len: layout.size(),
+                type_name,
+                call_site,
+            };
+            fun(ptr)
+        };
+
+        (unsafe { self.allocator.with_alloc(layout, fun) }).map_err(|e| match e {
+            MockRtAllocError::InvalidLayout => EFError::AllocInvalidLayout,
+            MockRtAllocError::OutOfMemory => EFError::AllocNoMem,
+        })
+    }
+
+    // Allocate memory for a `T` through the underlying allocator. Allocators
+    // may reject zero-sized layouts, so zero-sized types are placed at a
+    // dangling pointer instead:
+    #[track_caller]
+    fn with_alloc_t_int<T: Sized + 'static, F, R>(&self, fun: F) -> Result<R, EFError>
+    where
+        F: FnOnce(*mut T) -> R,
+    {
+        if core::mem::size_of::<T>() == 0 {
+            return Ok(fun(core::ptr::NonNull::<T>::dangling().as_ptr()));
+        }
+
+        self.with_alloc_int(
+            core::alloc::Layout::new::<T>(),
+            Some(core::any::type_name::<T>()),
+            |ptr| fun(ptr as *mut T),
+        )
+    }
+
+    fn scan_retained_pointers(&self, tracker: &MockRtAllocChain<'_>) {
+        let check = self.retained_pointer_check.borrow();
+
+        for (region_ptr, region_len) in check.scan_regions.iter().flatten() {
+            for idx in 0..*region_len {
+                // Use volatile reads, as foreign code may have modified this
+                // memory behind the compiler's back:
+                let value = unsafe { core::ptr::read_volatile(region_ptr.add(idx)) };
+
+                // Pointers into currently valid allocations are fine, even if
+                // they occupy memory of a previously released allocation. We
+                // only consider actual allocations here, as a tracker which
+                // considers all upgrades valid would otherwise accept any
+                // pointer:
+                if tracker.is_allocation_int(value as *mut ()) {
+                    continue;
+                }
+
+                // Report the most recently released allocation this value
+                // points into:
+                let expired = (0..MOCK_RT_EXPIRED_ALLOCATIONS)
+                    .map(|i| {
+                        (check.next_expired + MOCK_RT_EXPIRED_ALLOCATIONS - 1 - i)
+                            % MOCK_RT_EXPIRED_ALLOCATIONS
+                    })
+                    .filter_map(|i| check.expired[i].as_ref())
+                    .find(|alloc| value >= alloc.ptr && value - alloc.ptr < alloc.len);
+
+                if let Some(alloc) = expired {
+                    panic!(
+                        "Foreign memory at {:p} retains pointer {:#x} into released allocation \
+                         of type {} at {:#x} ({} bytes), allocated at {}!",
+                        unsafe { region_ptr.add(idx) },
+                        value,
+                        alloc.type_name.unwrap_or("<untyped>"),
+                        alloc.ptr,
+                        alloc.len,
+                        alloc.call_site,
+                    );
+                }
+            }
+        }
+    }
+
+    // Register the `len` bytes at `ptr` as an immutable allocation, for the
+    // duration of the closure:
+    fn with_immutable_allocation<F, R>(
+        &self,
+        ptr: *const (),
+        len: usize,
+        type_name: &'static str,
+        alloc_scope: &mut AllocScope<'_, MockRtAllocChain<'_>, ID>,
+        fun: F,
+    ) -> R
+    where
+        F: for<'b> FnOnce(&'b mut AllocScope<'_, MockRtAllocChain<'_>, ID>) -> R,
+    {
+        // Create a new AllocScope instance that wraps a new allocation tracker
+        // `Cons` list element that points to this allocation, and its
+        // predecessors:
+        let mut inner_alloc_scope = unsafe {
+            AllocScope::new(
+                MockRtAllocChain::Allocation(
+                    MockRtAllocation::new(ptr as *mut (), len, false, Some(type_name)),
+                    alloc_scope.tracker(),
+                ),
+                alloc_scope.id_imprint(),
+            )
+        };
+
+        // Hand a temporary reference to this new scope to the closure.
+        //
+        // We thus not only allocate, but also track allocations themselves on
+        // the stack, and there is nothing to clean up! The new
+        // `inner_alloc_scope` will simply go out of scope at the end of this
+        // function.
+        fun(&mut inner_alloc_scope)
+    }
+
+    fn lend_ref_int<T: Sized + 'static, F, R>(
+        &self,
+        t: &T,
+        alloc_scope: &mut AllocScope<'_, MockRtAllocChain<'_>, ID>,
+        access_scope: &mut AccessScope<ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(
+            EFRef<'_, ID, T>,
+            &'b mut AllocScope<'_, MockRtAllocChain<'_>, ID>,
+            &'b mut AccessScope<ID>,
+        ) -> R,
+    {
+        if self.id_imprint != alloc_scope.id_imprint()
+            || self.id_imprint != access_scope.id_imprint()
+        {
+            return Err(EFError::IDMismatch);
+        }
+
+        Ok(self.with_immutable_allocation(
+            t as *const T as *const (),
+            core::mem::size_of::<T>(),
+            core::any::type_name::<T>(),
+            alloc_scope,
+            |inner_alloc_scope| {
+                fun(
+                    unsafe {
+                        EFPtr::<T>::from(t as *const T as *mut T)
+                            .upgrade_unchecked(inner_alloc_scope.id_imprint())
+                    },
+                    inner_alloc_scope,
+                    access_scope,
+                )
+            },
+        ))
+    }
+
+    fn lend_slice_int<T: Sized + Copy + 'static, F, R>(
+        &self,
+        src: &[T],
+        alloc_scope: &mut AllocScope<'_, MockRtAllocChain<'_>, ID>,
+        access_scope: &mut AccessScope<ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(
+            EFSlice<'_, ID, T>,
+            &'b mut AllocScope<'_, MockRtAllocChain<'_>, ID>,
+            &'b mut AccessScope<ID>,
+        ) -> R,
+    {
+        if self.id_imprint != alloc_scope.id_imprint()
+            || self.id_imprint != access_scope.id_imprint()
+        {
+            return Err(EFError::IDMismatch);
+        }
+
+        // The MockRt does not restrict which memory foreign code can access,
+        // so we can lend Rust memory without copying it. For safety
+        // considerations, see `write_stacked_t`.
+        Ok(self.with_immutable_allocation(
+            src.as_ptr() as *const (),
+            core::mem::size_of_val(src),
+            core::any::type_name::<[T]>(),
+            alloc_scope,
+            |inner_alloc_scope| {
+                fun(
+                    unsafe {
+                        EFPtr::<T>::from(src.as_ptr() as *mut T)
+                            .upgrade_unchecked_slice(src.len(), inner_alloc_scope.id_imprint())
+                    },
+                    inner_alloc_scope,
+                    access_scope,
+                )
+            },
+        ))
+    }
+
     fn setup_callback_int<'a, C, F, R>(
         &self,
         callback: &'a mut C,
@@ -320,9 +863,33 @@ pub struct MockRtAllocation {
     ptr: *mut (),
     len: usize,
     mutable: bool,
+    type_name: Option<&'static str>,
+    // Checksum of immutable allocations, recorded before executing foreign
+    // code (see `MockRt::set_check_immutable_allocations`):
+    checksum: Cell<u64>,
 }
 
 impl MockRtAllocation {
+    fn new(ptr: *mut (), len: usize, mutable: bool, type_name: Option<&'static str>) -> Self {
+        MockRtAllocation {
+            ptr,
+            len,
+            mutable,
+            type_name,
+            checksum: Cell::new(0),
+        }
+    }
+
+    // 64-bit FNV-1a hash over the allocation's contents:
+    unsafe fn compute_checksum(&self) -> u64 {
+        (0..self.len).fold(0xcbf29ce484222325, |hash, offset| {
+            // Use volatile reads, as foreign code may have modified this
+            // memory behind the compiler's back:
+            let byte = unsafe { core::ptr::read_volatile((self.ptr as *const u8).add(offset)) };
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
     fn matches(&self, ptr: *mut (), len: usize, mutable: bool) -> bool {
         (ptr as usize) >= (self.ptr as usize)
             && ((ptr as usize)
@@ -414,6 +981,15 @@ impl<'a> MockRtAllocChain<'a> {
         })
     }
 
+    // Whether `ptr` points into any allocation of this chain, regardless of
+    // whether it considers all upgrades valid:
+    fn is_allocation_int(&self, ptr: *mut ()) -> bool {
+        self.iter().any(|elem| match elem {
+            MockRtAllocChain::Allocation(alloc, _) => alloc.matches(ptr, 1, false),
+            _ => false,
+        })
+    }
+
     fn next_callback_id(&self) -> usize {
         self.iter()
             .find_map(|elem| match elem {
@@ -521,12 +1097,24 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
         self.setup_callback_int(typecast_callback, alloc_scope, fun)
     }
 
-    fn execute<R, F: FnOnce() -> R>(
+    fn execute<R: EFType, F: FnOnce() -> R>(
         &self,
         alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
         access_scope: &mut AccessScope<Self::ID>,
         f: F,
     ) -> R {
+        match self.try_execute(alloc_scope, access_scope, f) {
+            Ok(res) => res,
+            Err(err) => panic!("Failed to execute foreign code: {:?}", err),
+        }
+    }
+
+    fn try_execute<R: EFType, F: FnOnce() -> R>(
+        &self,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        f: F,
+    ) -> Result<R, EFError> {
         if self.id_imprint != alloc_scope.id_imprint()
             || self.id_imprint != access_scope.id_imprint()
         {
@@ -538,7 +1126,52 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
             );
         }
 
-        f()
+        let immutable_allocations = || {
+            alloc_scope.tracker().iter().filter_map(|elem| match elem {
+                MockRtAllocChain::Allocation(alloc, _) if !alloc.mutable => Some(alloc),
+                _ => None,
+            })
+        };
+
+        if self.check_immutable_allocations {
+            for alloc in immutable_allocations() {
+                alloc.checksum.set(unsafe { alloc.compute_checksum() });
+            }
+        }
+
+        #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+        if self.poisoned.get() {
+            return Err(EFError::RuntimePoisoned);
+        }
+
+        #[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+        let res = if self.contain_faults {
+            fault::contain_faults(f).inspect_err(|_| self.poisoned.set(true))?
+        } else {
+            f()
+        };
+
+        #[cfg(not(all(feature = "std", target_os = "linux", target_arch = "x86_64")))]
+        let res = f();
+
+        if self.check_immutable_allocations {
+            for alloc in immutable_allocations() {
+                if alloc.checksum.get() != unsafe { alloc.compute_checksum() } {
+                    panic!(
+                        "Foreign code modified immutable allocation of type {} at {:p} ({} bytes)!",
+                        alloc.type_name.unwrap_or("<untyped>"),
+                        alloc.ptr,
+                        alloc.len,
+                    );
+                }
+            }
+        }
+
+        if self.check_retained_pointers {
+            self.scan_retained_pointers(alloc_scope.tracker());
+        }
+
+        Ok(res)
     }
 
     fn allocate_stacked_untracked_mut<F, R>(
@@ -549,10 +1182,8 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
     where
         F: FnOnce(*mut ()) -> R,
     {
-        // Simply proxy this to our underlying allocator:
-        (unsafe { self.allocator.with_alloc(layout, fun) }).map_err(|e| match e {
-            MockRtAllocError::InvalidLayout => EFError::AllocInvalidLayout,
-        })
+        // Proxy this to our underlying allocator:
+        self.with_alloc_int(layout, None, fun)
     }
 
     fn allocate_stacked_mut<F, R>(
@@ -575,11 +1206,7 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
             let mut inner_alloc_scope = unsafe {
                 AllocScope::new(
                     MockRtAllocChain::Allocation(
-                        MockRtAllocation {
-                            ptr,
-                            len: layout.size(),
-                            mutable: true,
-                        },
+                        MockRtAllocation::new(ptr, layout.size(), true, None),
                         alloc_scope.tracker(),
                     ),
                     alloc_scope.id_imprint(),
@@ -593,8 +1220,9 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
             // on the stack, and there is nothing to clean up! The new
             // `inner_alloc_scope` will simply go out of scope at the end of
             // this closure.
-            fun(ptr, &mut inner_alloc_scope)
+            self.with_shadow_region(ptr, layout.size(), || fun(ptr, &mut inner_alloc_scope))
         })
+        .and_then(|res| res)
     }
 
     fn allocate_stacked_t_mut<T: Sized + 'static, F, R>(
@@ -612,39 +1240,40 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
             return Err(EFError::IDMismatch);
         }
 
-        let t = UnsafeCell::new(MaybeUninit::<T>::uninit());
-
-        // Create a new AllocScope instance that wraps a new allocation
-        // tracker `Cons` list element that points to this allocation, and
-        // its predecessors:
-        let mut inner_alloc_scope = unsafe {
-            AllocScope::new(
-                MockRtAllocChain::Allocation(
-                    MockRtAllocation {
-                        ptr: &t as *const _ as *const _ as *mut _,
-                        len: core::mem::size_of::<T>(),
-                        mutable: true,
-                    },
-                    alloc_scope.tracker(),
-                ),
-                alloc_scope.id_imprint(),
-            )
-        };
+        self.with_alloc_t_int::<T, _, _>(|t| {
+            // Create a new AllocScope instance that wraps a new allocation
+            // tracker `Cons` list element that points to this allocation, and
+            // its predecessors:
+            let mut inner_alloc_scope = unsafe {
+                AllocScope::new(
+                    MockRtAllocChain::Allocation(
+                        MockRtAllocation::new(
+                            t as *mut (),
+                            core::mem::size_of::<T>(),
+                            true,
+                            Some(core::any::type_name::<T>()),
+                        ),
+                        alloc_scope.tracker(),
+                    ),
+                    alloc_scope.id_imprint(),
+                )
+            };
 
-        // Hand a temporary mutable reference to this new scope to the
-        // closure.
-        //
-        // We thus not only allocate, but also track allocations themselves
-        // on the stack, and there is nothing to clean up! The new
-        // `inner_alloc_scope` will simply go out of scope at the end of
-        // this closure.
-        Ok(fun(
-            unsafe {
-                EFPtr::<T>::from(&t as *const _ as *mut UnsafeCell<MaybeUninit<T>> as *mut T)
-                    .upgrade_unchecked_mut(alloc_scope.id_imprint())
-            },
-            &mut inner_alloc_scope,
-        ))
+            // Hand a temporary mutable reference to this new scope to the
+            // closure.
+            //
+            // We thus not only allocate, but also track allocations themselves
+            // on the stack, and there is nothing to clean up! The new
+            // `inner_alloc_scope` will simply go out of scope at the end of
+            // this closure.
+            self.with_shadow_region(t as *mut (), core::mem::size_of::<T>(), || {
+                fun(
+                    unsafe { EFPtr::<T>::from(t).upgrade_unchecked_mut(alloc_scope.id_imprint()) },
+                    &mut inner_alloc_scope,
+                )
+            })
+        })
+        .and_then(|res| res)
     }
 
     fn write_stacked_t<T: Sized + 'static, F, R>(
@@ -667,64 +1296,43 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
             return Err(EFError::IDMismatch);
         }
 
-        if self.zero_copy_immutable {
-            // We can't wrap `write_stacked_ref_t` here, as our `T: ?Copy`.
-
-            // While there are no guarantees that foreign code will uphold to
-            // the immutability requirement with the MockRt, we still don't use
-            // interior mutability here. This more closely simulates what a
-            // proper runtime with memory protection would do.
-            //
-            // The soundness of this depends on whether the foreign code is
-            // well-behaved, and whether the bindings correctly pass these
-            // pointers *const arguments:
-            let stored = t;
+        // We can't wrap `write_stacked_ref_t` here, as our `T: ?Copy`. As we
+        // own `t`, placing it into an allocation is a copy regardless of
+        // `zero_copy_immutable`.
 
-            // Create a new AllocScope instance that wraps a new allocation
-            // tracker `Cons` list element that points to this allocation, and
-            // its predecessors:
-            let mut inner_alloc_scope = unsafe {
-                AllocScope::new(
-                    MockRtAllocChain::Allocation(
-                        MockRtAllocation {
-                            ptr: &stored as *const _ as *const _ as *mut _,
-                            len: core::mem::size_of::<T>(),
-                            mutable: false,
+        // While there are no guarantees that foreign code will uphold to the
+        // immutability requirement with the MockRt, we still don't use
+        // interior mutability here. This more closely simulates what a proper
+        // runtime with memory protection would do.
+        //
+        // The soundness of this depends on whether the foreign code is
+        // well-behaved, and whether the bindings correctly pass these pointers
+        // *const arguments:
+        self.with_alloc_t_int::<T, _, _>(|stored| {
+            unsafe { stored.write(t) };
+
+            let ret = self.with_immutable_allocation(
+                stored as *const (),
+                core::mem::size_of::<T>(),
+                core::any::type_name::<T>(),
+                alloc_scope,
+                |inner_alloc_scope| {
+                    fun(
+                        unsafe {
+                            EFPtr::<T>::from(stored)
+                                .upgrade_unchecked(inner_alloc_scope.id_imprint())
                         },
-                        alloc_scope.tracker(),
-                    ),
-                    alloc_scope.id_imprint(),
-                )
-            };
-
-            // Hand a temporary immutable reference to this new scope to the
-            // closure.
-            //
-            // We thus not only allocate, but also track allocations themselves
-            // on the stack, and there is nothing to clean up! The new
-            // `inner_alloc_scope` will simply go out of scope at the end of
-            // this closure.
-            Ok(fun(
-                unsafe {
-                    EFPtr::<T>::from(
-                        &stored as *const _ as *mut UnsafeCell<MaybeUninit<T>> as *mut T,
+                        inner_alloc_scope,
+                        access_scope,
                     )
-                    .upgrade_unchecked(alloc_scope.id_imprint())
                 },
-                &mut inner_alloc_scope,
-                access_scope,
-            ))
-        } else {
-            // Fall back onto default behavior:
-            self.write_stacked_t_mut(
-                t,
-                alloc_scope,
-                access_scope,
-                |allocation, new_alloc_scope, new_access_scope| {
-                    fun(allocation.as_immut(), new_alloc_scope, new_access_scope)
-                },
-            )
-        }
+            );
+
+            // Drop `t` before the release policy is applied to its allocation:
+            unsafe { core::ptr::drop_in_place(stored) };
+
+            ret
+        })
     }
 
     fn write_stacked_ref_t<T: Sized + Copy + 'static, F, R>(
@@ -741,61 +1349,52 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
             &'b mut AccessScope<Self::ID>,
         ) -> R,
     {
-        if self.id_imprint != alloc_scope.id_imprint()
-            || self.id_imprint != access_scope.id_imprint()
-        {
-            return Err(EFError::IDMismatch);
+        if self.zero_copy_immutable {
+            // For safety considerations, see `write_stacked_t`.
+            self.lend_ref_int(t, alloc_scope, access_scope, fun)
+        } else {
+            // Copy `t` into an allocation of the underlying allocator, and
+            // lend out the copy:
+            self.with_alloc_t_int::<T, _, _>(|stored| {
+                unsafe { stored.write(*t) };
+                self.lend_ref_int(unsafe { &*stored }, alloc_scope, access_scope, fun)
+            })?
         }
+    }
 
+    fn write_stacked_slice<T: Sized + Copy + 'static, F, R>(
+        &self,
+        src: &[T],
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(
+            EFSlice<'_, Self::ID, T>,
+            &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &'b mut AccessScope<Self::ID>,
+        ) -> R,
+    {
         if self.zero_copy_immutable {
             // For safety considerations, see `write_stacked_t`.
-
-            // Create a new AllocScope instance that wraps a new allocation
-            // tracker `Cons` list element that points to this allocation, and
-            // its predecessors:
-            let mut inner_alloc_scope = unsafe {
-                AllocScope::new(
-                    MockRtAllocChain::Allocation(
-                        MockRtAllocation {
-                            ptr: t as *const _ as *const _ as *mut _,
-                            len: core::mem::size_of::<T>(),
-                            mutable: false,
-                        },
-                        alloc_scope.tracker(),
-                    ),
-                    alloc_scope.id_imprint(),
-                )
-            };
-
-            // Hand a temporary immutable reference to this new scope to the
-            // closure.
-            //
-            // We thus not only allocate, but also track allocations themselves
-            // on the stack, and there is nothing to clean up! The new
-            // `inner_alloc_scope` will simply go out of scope at the end of
-            // this closure.
-            Ok(fun(
-                unsafe {
-                    EFPtr::<T>::from(t as *const _ as *mut UnsafeCell<MaybeUninit<T>> as *mut T)
-                        .upgrade_unchecked(alloc_scope.id_imprint())
-                },
-                &mut inner_alloc_scope,
-                access_scope,
-            ))
+            self.lend_slice_int(src, alloc_scope, access_scope, fun)
         } else {
-            // Fall back onto default behavior:
-            self.write_stacked_ref_t_mut(
-                t,
-                alloc_scope,
-                access_scope,
-                |allocation, new_alloc_scope, new_access_scope| {
-                    fun(allocation.as_immut(), new_alloc_scope, new_access_scope)
-                },
-            )
+            // Copy `src` into an allocation of the underlying allocator, and
+            // lend out the copy:
+            let layout = core::alloc::Layout::array::<T>(src.len())
+                .map_err(|_| EFError::AllocInvalidLayout)?;
+            self.allocate_stacked_untracked_mut(layout, |ptr| {
+                let copy = unsafe {
+                    core::ptr::copy_nonoverlapping(src.as_ptr(), ptr as *mut T, src.len());
+                    core::slice::from_raw_parts(ptr as *const T, src.len())
+                };
+                self.lend_slice_int(copy, alloc_scope, access_scope, fun)
+            })?
         }
     }
 
-    fn write_stacked_slice<T: Sized + Copy + 'static, F, R>(
+    fn lend_slice<T: Sized + Copy + 'static, F, R>(
         &self,
         src: &[T],
         alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
@@ -808,6 +1407,25 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
             &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
             &'b mut AccessScope<Self::ID>,
         ) -> R,
+    {
+        // Lending Rust memory is only zero-copy if `zero_copy_immutable` is
+        // set, exactly as for `write_stacked_slice`:
+        self.write_stacked_slice(src, alloc_scope, access_scope, fun)
+    }
+
+    fn lend_slice_mut<T: Sized + Copy + EFFromBytes + 'static, F, R>(
+        &self,
+        dst: &mut [T],
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(
+            EFMutSlice<'_, Self::ID, T>,
+            &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &'b mut AccessScope<Self::ID>,
+        ) -> R,
     {
         if self.id_imprint != alloc_scope.id_imprint()
             || self.id_imprint != access_scope.id_imprint()
@@ -815,44 +1433,466 @@ unsafe impl<ID: EFID, A: MockRtAllocator> EncapfnRt for MockRt<ID, A> {
             return Err(EFError::IDMismatch);
         }
 
-        if self.zero_copy_immutable {
-            // For safety considerations, see `write_stacked_t`.
-
-            // Create a new AllocScope instance that wraps a new allocation
-            // tracker `Cons` list element that points to this allocation, and
-            // its predecessors:
-            let mut inner_alloc_scope = unsafe {
-                AllocScope::new(
-                    MockRtAllocChain::Allocation(
-                        MockRtAllocation {
-                            ptr: src as *const _ as *const _ as *mut _,
-                            len: core::mem::size_of::<T>() * src.len(),
-                            mutable: false,
-                        },
-                        alloc_scope.tracker(),
+        // Foreign code may write to this memory directly. This is sound, as
+        // `T: EFFromBytes` ensures that any bit pattern is a valid `T`, and
+        // `dst` is mutably borrowed for the duration of this call:
+        let mut inner_alloc_scope = unsafe {
+            AllocScope::new(
+                MockRtAllocChain::Allocation(
+                    MockRtAllocation::new(
+                        dst as *mut _ as *mut (),
+                        core::mem::size_of_val(dst),
+                        true,
+                        Some(core::any::type_name::<[T]>()),
                     ),
-                    alloc_scope.id_imprint(),
-                )
-            };
+                    alloc_scope.tracker(),
+                ),
+                alloc_scope.id_imprint(),
+            )
+        };
 
-            // Hand a temporary immutable reference to this new scope to the
-            // closure.
-            //
-            // We thus not only allocate, but also track allocations themselves
-            // on the stack, and there is nothing to clean up! The new
-            // `inner_alloc_scope` will simply go out of scope at the end of
-            // this closure.
-            Ok(fun(
-                unsafe {
-                    EFPtr::<T>::from(src as *const _ as *mut UnsafeCell<MaybeUninit<T>> as *mut T)
-                        .upgrade_unchecked_slice(src.len(), alloc_scope.id_imprint())
+        Ok(fun(
+            unsafe {
+                EFPtr::<T>::from(dst.as_mut_ptr())
+                    .upgrade_unchecked_slice_mut(dst.len(), alloc_scope.id_imprint())
+            },
+            &mut inner_alloc_scope,
+            access_scope,
+        ))
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "Foreign code modified immutable allocation of type u32")]
+fn test_check_immutable_allocations() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    extern "C" fn misbehaving_write(ptr: *const u32) {
+        unsafe { *(ptr as *mut u32) = 0 };
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        rt.set_check_immutable_allocations(true);
+
+        rt.write_stacked_ref_t(
+            &42_u32,
+            &mut alloc_scope,
+            &mut access_scope,
+            |arg, alloc_scope, access_scope| {
+                // Immutable allocations can't be upgraded mutably:
+                assert!(arg.as_ptr().upgrade_mut(alloc_scope).is_none());
+                rt.execute(alloc_scope, access_scope, || {
+                    misbehaving_write(arg.as_ptr().0)
+                });
+            },
+        )
+        .unwrap();
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_lend_slice_zero_copy_immutable() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    let src = [1_u32, 2, 3];
+
+    for zero_copy_immutable in [false, true] {
+        EFLifetimeBranding::new::<()>(|brand| {
+            let (rt, mut alloc_scope, mut access_scope) =
+                unsafe { MockRt::new(zero_copy_immutable, false, HeapAllocator, brand) };
+
+            rt.lend_slice(
+                &src,
+                &mut alloc_scope,
+                &mut access_scope,
+                |lent, _alloc_scope, access_scope| {
+                    assert_eq!(
+                        core::ptr::eq(lent.as_ptr().0, src.as_ptr()),
+                        zero_copy_immutable
+                    );
+                    assert_eq!(&*lent.validate(access_scope).unwrap(), &src);
                 },
-                &mut inner_alloc_scope,
-                access_scope,
-            ))
-        } else {
-            // Fall back onto default behavior:
-            self.write_stacked_slice_from_iter(src.iter().copied(), alloc_scope, access_scope, fun)
+            )
+            .unwrap();
+        });
+    }
+}
+
+#[test]
+fn test_release_policy() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::RELEASE_POISON_PATTERN;
+
+    // Hands out the same buffer for every allocation, which remains
+    // inspectable after release:
+    struct BufferAllocator(*mut [u64; 4]);
+
+    impl MockRtAllocator for BufferAllocator {
+        unsafe fn with_alloc<R, F: FnOnce(*mut ()) -> R>(
+            &self,
+            layout: core::alloc::Layout,
+            f: F,
+        ) -> Result<R, MockRtAllocError> {
+            if layout.size() > core::mem::size_of::<[u64; 4]>() || layout.align() > 8 {
+                return Err(MockRtAllocError::InvalidLayout);
+            }
+            Ok(f(self.0 as *mut ()))
         }
     }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let mut buffer = [0_u64; 4];
+        let buffer: *mut [u64; 4] = &mut buffer;
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, BufferAllocator(buffer), brand) };
+
+        for (policy, expected) in [
+            (ReleasePolicy::Leave, *b"secret"),
+            (ReleasePolicy::Zeroize, [0; 6]),
+            (ReleasePolicy::Poison, [0xEF, 0xBE, 0xAD, 0xDE, 0xEF, 0xBE]),
+        ] {
+            rt.set_release_policy(policy);
+            rt.write_stacked_slice_mut(
+                b"secret",
+                &mut alloc_scope,
+                &mut access_scope,
+                |_, _, _| (),
+            )
+            .unwrap();
+            assert_eq!(unsafe { *(buffer as *const [u8; 6]) }, expected);
+
+            // Arguments passed by value are copied into allocations of the
+            // underlying allocator as well:
+            unsafe { *buffer = [0; 4] };
+            rt.write_stacked_t(
+                *b"secret",
+                &mut alloc_scope,
+                &mut access_scope,
+                |_, _, _| (),
+            )
+            .unwrap();
+            assert_eq!(unsafe { *(buffer as *const [u8; 6]) }, expected);
+
+            unsafe { *buffer = [0; 4] };
+            rt.write_stacked_ref_t(b"secret", &mut alloc_scope, &mut access_scope, |_, _, _| ())
+                .unwrap();
+            assert_eq!(unsafe { *(buffer as *const [u8; 6]) }, expected);
+
+            // The policy is applied even if the closure panics:
+            #[cfg(feature = "std")]
+            {
+                unsafe { *buffer = [0; 4] };
+                let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    rt.write_stacked_slice_mut(
+                        b"secret",
+                        &mut alloc_scope,
+                        &mut access_scope,
+                        |_, _, _| panic!(),
+                    )
+                }));
+                assert!(res.is_err());
+                assert_eq!(unsafe { *(buffer as *const [u8; 6]) }, expected);
+            }
+        }
+
+        assert_eq!(RELEASE_POISON_PATTERN, [0xEF, 0xBE, 0xAD, 0xDE]);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "into released allocation of type <untyped>")]
+fn test_check_retained_pointers() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    // A library global, retaining the last argument passed:
+    static mut LAST_ARG: usize = 0;
+
+    extern "C" fn retain_arg(ptr: *const u32) {
+        unsafe { LAST_ARG = ptr as usize };
+    }
+
+    extern "C" fn noop() {}
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        rt.set_check_retained_pointers(true);
+        assert!(unsafe {
+            rt.add_retained_pointer_scan_region(
+                core::ptr::addr_of!(LAST_ARG) as *const (),
+                core::mem::size_of::<usize>(),
+            )
+        });
+
+        rt.write_stacked_slice(
+            &[1_u32, 2, 3],
+            &mut alloc_scope,
+            &mut access_scope,
+            |arg, alloc_scope, access_scope| {
+                // Retaining a pointer to a valid allocation is fine:
+                rt.execute(alloc_scope, access_scope, || retain_arg(arg.as_ptr().0));
+            },
+        )
+        .unwrap();
+
+        // The argument's allocation has been released:
+        rt.execute(&mut alloc_scope, &mut access_scope, || noop());
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "into released allocation of type <untyped>")]
+fn test_check_retained_pointers_all_upgrades_valid() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    static mut LAST_ARG: usize = 0;
+
+    extern "C" fn retain_arg(ptr: *const u32) {
+        unsafe { LAST_ARG = ptr as usize };
+    }
+
+    extern "C" fn noop() {}
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        // Even though this runtime permits upgrading any pointer, released
+        // allocations are still detected:
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, true, HeapAllocator, brand) };
+        rt.set_check_retained_pointers(true);
+        assert!(unsafe {
+            rt.add_retained_pointer_scan_region(
+                core::ptr::addr_of!(LAST_ARG) as *const (),
+                core::mem::size_of::<usize>(),
+            )
+        });
+
+        rt.write_stacked_slice(
+            &[1_u32, 2, 3],
+            &mut alloc_scope,
+            &mut access_scope,
+            |arg, alloc_scope, access_scope| {
+                rt.execute(alloc_scope, access_scope, || retain_arg(arg.as_ptr().0));
+            },
+        )
+        .unwrap();
+
+        rt.execute(&mut alloc_scope, &mut access_scope, || noop());
+    });
+}
+
+#[cfg(all(feature = "std", feature = "shadow_memory"))]
+#[test]
+#[should_panic(expected = "byte 3 of the 4-byte allocation")]
+fn test_shadow_memory() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    // Writes the second-to-last byte of its argument:
+    extern "C" fn foreign_write(ptr: *mut u8) {
+        unsafe { *ptr.add(2) = 42 };
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        rt.set_shadow_memory(true);
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(4, &mut alloc_scope, |buf, alloc_scope| {
+            buf.get(0).unwrap().write(1, &mut access_scope);
+            buf.get(1).unwrap().write(2, &mut access_scope);
+            rt.execute(alloc_scope, &mut access_scope, || {
+                foreign_write(buf.as_ptr().0)
+            });
+
+            // Bytes written by either side may be read:
+            assert_eq!(
+                *buf.get_range(0..3)
+                    .unwrap()
+                    .validate(&access_scope)
+                    .unwrap(),
+                [1, 2, 42]
+            );
+
+            // The last byte was never written:
+            buf.validate(&access_scope);
+        })
+        .unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", feature = "shadow_memory"))]
+#[test]
+fn test_shadow_memory_copy() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        rt.set_shadow_memory(true);
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(8, &mut alloc_scope, |buf, _| {
+            // Copies mark their destination as initialized, even when copying
+            // bytes which happen to match the poison pattern:
+            let (src, dst) = buf.split_at(4).unwrap();
+            src.copy_from_slice(&[0xA5; 4], &access_scope);
+            dst.copy_from_efslice(&src.as_immut(), &mut access_scope);
+            assert_eq!(*dst.validate(&access_scope).unwrap(), [0xA5; 4]);
+
+            buf.copy_within(2..6, 4, &mut access_scope);
+            src.swap_with(&dst, &mut access_scope);
+            assert!(buf.validate_detailed(&access_scope).is_ok());
+        })
+        .unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", feature = "shadow_memory"))]
+#[test]
+#[should_panic(expected = "byte 0 of the 8-byte allocation")]
+fn test_shadow_memory_copy_uninit() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        rt.set_shadow_memory(true);
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(8, &mut alloc_scope, |buf, _| {
+            let (src, dst) = buf.split_at(4).unwrap();
+            dst.copy_from_efslice(&src.as_immut(), &mut access_scope);
+        })
+        .unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", feature = "shadow_memory"))]
+#[test]
+#[should_panic(expected = "byte 1 of the 2-byte allocation")]
+fn test_shadow_memory_validate_detailed() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        rt.set_shadow_memory(true);
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(2, &mut alloc_scope, |buf, _| {
+            buf.get(0).unwrap().write(1, &mut access_scope);
+            let _ = buf.validate_detailed(&access_scope);
+        })
+        .unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", feature = "shadow_memory"))]
+#[test]
+#[should_panic(expected = "byte 2 of the 4-byte allocation")]
+fn test_shadow_memory_short_iter() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        rt.set_shadow_memory(true);
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(4, &mut alloc_scope, |buf, _alloc_scope| {
+            // A short iterator panics, but only marks the elements written:
+            let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                buf.write_from_iter([1, 2].into_iter(), &access_scope);
+            }));
+            assert!(res.is_err());
+            assert_eq!(
+                *buf.get_range(..2).unwrap().validate(&access_scope).unwrap(),
+                [1, 2]
+            );
+
+            buf.validate(&access_scope);
+        })
+        .unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", feature = "shadow_memory"))]
+#[test]
+#[should_panic(expected = "byte 2 of the 4-byte allocation")]
+fn test_shadow_memory_unaligned_short_iter() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        rt.set_shadow_memory(true);
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(4, &mut alloc_scope, |buf, alloc_scope| {
+            let unaligned = buf
+                .as_ptr()
+                .upgrade_unaligned_slice_mut(4, alloc_scope)
+                .unwrap();
+
+            // A short iterator panics, but only marks the elements written:
+            let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                unaligned.write_from_iter([1, 2].into_iter(), &mut access_scope)
+            }));
+            assert!(res.is_err());
+            assert_eq!(
+                *buf.get_range(..2).unwrap().validate(&access_scope).unwrap(),
+                [1, 2]
+            );
+
+            buf.validate(&access_scope);
+        })
+        .unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", target_os = "linux", target_arch = "x86_64"))]
+#[test]
+fn test_contain_faults() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+
+    extern "C" fn bad_write(addr: usize) {
+        unsafe { core::ptr::write_volatile(addr as *mut u32, 42) };
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+        // Safety: the closures below only invoke foreign functions:
+        unsafe { rt.set_contain_faults(true) };
+
+        let addr = core::hint::black_box(8);
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || bad_write(addr)),
+            Err(EFError::ForeignFault { signal: 11, addr })
+        );
+
+        // The runtime is poisoned until reset:
+        assert!(rt.is_poisoned());
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || 42),
+            Err(EFError::RuntimePoisoned)
+        );
+
+        rt.clear_poison();
+        assert_eq!(
+            rt.try_execute(&mut alloc_scope, &mut access_scope, || 42),
+            Ok(42)
+        );
+    });
 }
@@ -0,0 +1,219 @@
+use core::cell::Cell;
+
+/// An allocator wrapper which limits the number of bytes concurrently
+/// allocated through the wrapped allocator.
+///
+/// Allocations which would exceed the quota fail with
+/// [`MockRtAllocError::OutOfMemory`](super::MockRtAllocError::OutOfMemory),
+/// which the `MockRt` reports as [`EFError::AllocNoMem`]. The quota applies to
+/// the size of each allocation as requested from this allocator, and thus
+/// does not include padding added by wrapped allocators, nor the runtime's
+/// internal bookkeeping such as shadow-memory bitmaps.
+///
+/// Only memory allocated through the runtime is accounted for. Memory that
+/// foreign code allocates itself, such as by calling `malloc`, is shared with
+/// this process and can't be limited separately. The `ForkRt` limits such
+/// allocations through its quota.
+///
+/// [`EFError::AllocNoMem`]: crate::EFError::AllocNoMem
+pub struct QuotaAllocator<A: super::MockRtAllocator> {
+    inner: A,
+    quota: usize,
+    used: Cell<usize>,
+    peak: Cell<usize>,
+    denied: Cell<usize>,
+}
+
+impl<A: super::MockRtAllocator> QuotaAllocator<A> {
+    /// Wrap `inner`, allowing at most `quota` bytes to be allocated at any
+    /// point in time.
+    pub fn new(inner: A, quota: usize) -> Self {
+        QuotaAllocator {
+            inner,
+            quota,
+            used: Cell::new(0),
+            peak: Cell::new(0),
+            denied: Cell::new(0),
+        }
+    }
+
+    /// The number of bytes which may be allocated at any point in time.
+    pub fn quota(&self) -> usize {
+        self.quota
+    }
+
+    /// Change the quota. Does not affect existing allocations, even if they
+    /// exceed the new quota.
+    pub fn set_quota(&mut self, quota: usize) {
+        self.quota = quota;
+    }
+
+    /// The number of bytes currently allocated.
+    pub fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    /// The highest number of bytes allocated at any point in time.
+    pub fn peak(&self) -> usize {
+        self.peak.get()
+    }
+
+    /// The number of allocations which failed as they would have exceeded
+    /// the quota.
+    pub fn denied(&self) -> usize {
+        self.denied.get()
+    }
+}
+
+impl<A: super::MockRtAllocator> super::MockRtAllocator for QuotaAllocator<A> {
+    #[track_caller]
+    unsafe fn with_alloc<R, F: FnOnce(*mut ()) -> R>(
+        &self,
+        layout: core::alloc::Layout,
+        f: F,
+    ) -> Result<R, super::MockRtAllocError> {
+        let used = match self.used.get().checked_add(layout.size()) {
+            Some(used) if used <= self.quota => used,
+            _ => {
+                self.denied.set(self.denied.get() + 1);
+                return Err(super::MockRtAllocError::OutOfMemory);
+            }
+        };
+
+        // Release the allocation's bytes from the quota even if `f` unwinds:
+        struct Release<'a> {
+            used: &'a Cell<usize>,
+            size: usize,
+        }
+
+        impl Drop for Release<'_> {
+            fn drop(&mut self) {
+                self.used.set(self.used.get() - self.size);
+            }
+        }
+
+        self.used.set(used);
+        self.peak.set(core::cmp::max(self.peak.get(), used));
+        let _release = Release {
+            used: &self.used,
+            size: layout.size(),
+        };
+
+        self.inner.with_alloc(layout, f)
+    }
+
+    // The runtime's bookkeeping does not count against the quota:
+    #[track_caller]
+    unsafe fn with_internal_alloc<R, F: FnOnce(*mut ()) -> R>(
+        &self,
+        layout: core::alloc::Layout,
+        f: F,
+    ) -> Result<R, super::MockRtAllocError> {
+        unsafe { self.inner.with_internal_alloc(layout, f) }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_quota_allocator() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+    use crate::rt::EncapfnRt;
+    use crate::EFError;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, _access_scope) =
+            unsafe { MockRt::new(false, false, QuotaAllocator::new(HeapAllocator, 64), brand) };
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(48, &mut alloc_scope, |_buf, alloc_scope| {
+            assert_eq!(rt.allocator().used(), 48);
+
+            // Nested allocations count against the same quota:
+            assert_eq!(
+                rt.allocate_stacked_slice_mut::<u8, _, _>(32, alloc_scope, |_, _| ()),
+                Err(EFError::AllocNoMem)
+            );
+            rt.allocate_stacked_slice_mut::<u8, _, _>(16, alloc_scope, |_, _| ())
+                .unwrap();
+        })
+        .unwrap();
+
+        assert_eq!(rt.allocator().used(), 0);
+        assert_eq!(rt.allocator().peak(), 64);
+        assert_eq!(rt.allocator().denied(), 1);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_quota_allocator_stacked_values() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+    use crate::rt::EncapfnRt;
+    use crate::EFError;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, QuotaAllocator::new(HeapAllocator, 16), brand) };
+
+        rt.allocate_stacked_t_mut::<[u64; 2], _, _>(&mut alloc_scope, |_, alloc_scope| {
+            assert_eq!(rt.allocator().used(), 16);
+            assert_eq!(
+                rt.allocate_stacked_t_mut::<u8, _, _>(alloc_scope, |_, _| ()),
+                Err(EFError::AllocNoMem)
+            );
+        })
+        .unwrap();
+
+        // Values passed by value and by reference are copied into allocations
+        // counting against the quota:
+        rt.write_stacked_t(
+            [0_u32; 3],
+            &mut alloc_scope,
+            &mut access_scope,
+            |_, alloc_scope, access_scope| {
+                assert_eq!(rt.allocator().used(), 12);
+                assert_eq!(
+                    rt.write_stacked_ref_t(&0_u64, alloc_scope, access_scope, |_, _, _| ()),
+                    Err(EFError::AllocNoMem)
+                );
+                assert_eq!(
+                    rt.write_stacked_slice(&[0_u8; 5], alloc_scope, access_scope, |_, _, _| ()),
+                    Err(EFError::AllocNoMem)
+                );
+                rt.write_stacked_ref_t(&0_u32, alloc_scope, access_scope, |_, _, _| {
+                    assert_eq!(rt.allocator().used(), 16);
+                })
+                .unwrap();
+            },
+        )
+        .unwrap();
+
+        assert_eq!(rt.allocator().used(), 0);
+        assert_eq!(rt.allocator().denied(), 3);
+    });
+}
+
+#[cfg(all(feature = "std", feature = "shadow_memory"))]
+#[test]
+fn test_quota_allocator_shadow_memory() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+    use crate::rt::EncapfnRt;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (mut rt, mut alloc_scope, _access_scope) =
+            unsafe { MockRt::new(false, false, QuotaAllocator::new(HeapAllocator, 64), brand) };
+        rt.set_shadow_memory(true);
+
+        // The shadow bitmap does not count against the quota:
+        rt.allocate_stacked_slice_mut::<u8, _, _>(64, &mut alloc_scope, |_, _| {
+            assert_eq!(rt.allocator().used(), 64);
+        })
+        .unwrap();
+        assert_eq!(rt.allocator().denied(), 0);
+    });
+}
@@ -0,0 +1,182 @@
+use core::panic::Location;
+
+/// Byte pattern written into redzones.
+pub const REDZONE_CANARY: u8 = 0xCA;
+
+/// An allocator wrapper which pads every allocation of the wrapped allocator
+/// with canary-filled redzones.
+///
+/// The redzones are verified when the allocation is released, and overflows
+/// and underflows into them are reported by panicking with the allocation's
+/// layout and call site. Allocations handed out by this allocator (and thus
+/// the allocations tracked by the `MockRt`) do not include the redzones.
+pub struct RedzoneAllocator<A: super::MockRtAllocator> {
+    inner: A,
+    redzone_size: usize,
+}
+
+impl<A: super::MockRtAllocator> RedzoneAllocator<A> {
+    /// Wrap `inner`, placing `redzone_size` bytes of canaries before and
+    /// after every allocation.
+    ///
+    /// The redzone preceding an allocation may be larger, to maintain the
+    /// allocation's alignment.
+    pub fn new(inner: A, redzone_size: usize) -> Self {
+        RedzoneAllocator {
+            inner,
+            redzone_size,
+        }
+    }
+
+    // Return the offset and length of the first run of corrupted canaries in
+    // the `len` bytes at `ptr`, if any:
+    unsafe fn find_corruption(ptr: *const u8, len: usize) -> Option<(usize, usize)> {
+        // Use volatile reads, as foreign code may have modified this memory
+        // behind the compiler's back:
+        let corrupted =
+            |offset: usize| unsafe { core::ptr::read_volatile(ptr.add(offset)) } != REDZONE_CANARY;
+
+        let first = (0..len).find(|offset| corrupted(*offset))?;
+        let last = (first..len).rfind(|offset| corrupted(*offset)).unwrap();
+        Some((first, last - first + 1))
+    }
+}
+
+impl<A: super::MockRtAllocator> super::MockRtAllocator for RedzoneAllocator<A> {
+    #[track_caller]
+    unsafe fn with_alloc<R, F: FnOnce(*mut ()) -> R>(
+        &self,
+        layout: core::alloc::Layout,
+        f: F,
+    ) -> Result<R, super::MockRtAllocError> {
+        let call_site = Location::caller();
+
+        let front_size = self
+            .redzone_size
+            .checked_next_multiple_of(layout.align())
+            .ok_or(super::MockRtAllocError::InvalidLayout)?;
+        let padded_layout = front_size
+            .checked_add(layout.size())
+            .and_then(|size| size.checked_add(self.redzone_size))
+            .and_then(|size| core::alloc::Layout::from_size_align(size, layout.align()).ok())
+            .ok_or(super::MockRtAllocError::InvalidLayout)?;
+
+        // The inner allocator may call into this closure through foreign
+        // frames, such as the `StackAllocator`'s trampoline, which must not be
+        // unwound. Thus we only record violations here, and report them once
+        // the inner allocator has returned:
+        let (ret, alloc_ptr, underflow, overflow) =
+            self.inner.with_alloc(padded_layout, |padded_ptr| {
+                let front_ptr = padded_ptr as *mut u8;
+                let alloc_ptr = unsafe { front_ptr.add(front_size) };
+                let back_ptr = unsafe { alloc_ptr.add(layout.size()) };
+
+                unsafe {
+                    core::ptr::write_bytes(front_ptr, REDZONE_CANARY, front_size);
+                    core::ptr::write_bytes(back_ptr, REDZONE_CANARY, self.redzone_size);
+                }
+
+                let ret = f(alloc_ptr as *mut ());
+
+                let underflow = unsafe { Self::find_corruption(front_ptr, front_size) };
+                let overflow = unsafe { Self::find_corruption(back_ptr, self.redzone_size) };
+
+                (ret, alloc_ptr, underflow, overflow)
+            })?;
+
+        if let Some((offset, len)) = underflow {
+            panic!(
+                "Underflow of allocation with {:?} at {:p}, allocated at {}: {} bytes \
+                 starting {} bytes before the allocation!",
+                layout,
+                alloc_ptr,
+                call_site,
+                len,
+                front_size - offset,
+            );
+        }
+
+        if let Some((offset, len)) = overflow {
+            panic!(
+                "Overflow of allocation with {:?} at {:p}, allocated at {}: {} bytes \
+                 starting {} bytes past the allocation!",
+                layout, alloc_ptr, call_site, len, offset,
+            );
+        }
+
+        Ok(ret)
+    }
+
+    // Internal allocations are never accessed by foreign code, so there is no
+    // use in padding them:
+    #[track_caller]
+    unsafe fn with_internal_alloc<R, F: FnOnce(*mut ()) -> R>(
+        &self,
+        layout: core::alloc::Layout,
+        f: F,
+    ) -> Result<R, super::MockRtAllocError> {
+        unsafe { self.inner.with_internal_alloc(layout, f) }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic(expected = "Overflow of allocation with Layout { size: 4, align: 1 (1 << 0) }")]
+fn test_redzone_overflow() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+    use crate::rt::EncapfnRt;
+    use crate::types::EFPtr;
+
+    extern "C" fn off_by_one_fill(buf: *mut u8, len: usize) {
+        for i in 0..=len {
+            unsafe { *buf.add(i) = 0 };
+        }
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, RedzoneAllocator::new(HeapAllocator, 8), brand) };
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(4, &mut alloc_scope, |buf, alloc_scope| {
+            // Redzones are not accessible through the AllocTracker:
+            assert!(EFPtr::from(buf.as_ptr().0.wrapping_add(4))
+                .upgrade_slice_mut(1, alloc_scope)
+                .is_none());
+            rt.execute(alloc_scope, &mut access_scope, || {
+                off_by_one_fill(buf.as_ptr().0, buf.len())
+            });
+        })
+        .unwrap();
+    });
+}
+
+// Violations must be reported without unwinding through the stack allocator's
+// foreign frames:
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+#[test]
+#[should_panic(expected = "Underflow of allocation with Layout { size: 4, align: 1 (1 << 0) }")]
+fn test_redzone_underflow_stack_alloc() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::stack_alloc::{StackAllocator, StackFrameAllocAMD64};
+    use crate::rt::mock::MockRt;
+    use crate::rt::EncapfnRt;
+
+    extern "C" fn underflowing_fill(buf: *mut u8) {
+        unsafe { *buf.sub(1) = 0 };
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let allocator = RedzoneAllocator::new(StackAllocator::<StackFrameAllocAMD64>::new(), 8);
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, allocator, brand) };
+
+        rt.allocate_stacked_slice_mut::<u8, _, _>(4, &mut alloc_scope, |buf, alloc_scope| {
+            rt.execute(alloc_scope, &mut access_scope, || {
+                underflowing_fill(buf.as_ptr().0)
+            });
+        })
+        .unwrap();
+    });
+}
@@ -1,11 +1,21 @@
+#[cfg_attr(
+    feature = "nightly",
+    doc(cfg(all(feature = "std", target_os = "linux")))
+)]
+#[cfg(all(feature = "std", any(target_os = "linux", doc)))]
+pub mod fork;
+pub mod frame;
 pub mod mock;
 pub mod rv32i_c;
 pub mod sysv_amd64;
 
 use crate::abi::EncapfnABI;
 use crate::branding::EFID;
+use crate::marshal::{EFMarshal, EFMarshalArena};
+use crate::rt::frame::EFStackedFrame;
 use crate::types::{
-    AccessScope, AllocScope, AllocTracker, EFMutRef, EFMutSlice, EFPtr, EFRef, EFSlice,
+    AccessScope, AllocScope, AllocTracker, EFCopy, EFFromBytes, EFMutRef, EFMutSlice, EFPtr, EFRef,
+    EFSlice, EFType,
 };
 use crate::EFError;
 
@@ -17,6 +27,72 @@ pub trait CallbackReturn {
     fn set_return_register(&mut self, reg: usize, value: usize) -> bool;
 }
 
+/// Initialization of out-parameter allocations, before they are handed to
+/// foreign code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutParamInit {
+    /// Leave the allocation uninitialized.
+    Uninit,
+    /// Zero all bytes of the allocation.
+    Zeroed,
+}
+
+/// Outcome of a call to a foreign function which fills a caller-provided
+/// `(buf, len)` output buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutBufLen {
+    /// The function wrote this many bytes of output into the buffer, not
+    /// including any NUL terminator. Output which fills the entire buffer may
+    /// have been truncated, and is retried with a larger buffer.
+    Written(usize),
+    /// The buffer was too small. Holds the required length, if the function
+    /// reported it. Otherwise, the buffer size will be doubled.
+    TooSmall(Option<usize>),
+    /// The function failed.
+    Failed,
+}
+
+/// Policy applied to the contents of stacked allocations once their scope
+/// ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleasePolicy {
+    /// Leave the contents of released allocations untouched.
+    Leave,
+    /// Overwrite released allocations with zeroes, scrubbing any sensitive
+    /// data they contain.
+    Zeroize,
+    /// Overwrite released allocations with [`RELEASE_POISON_PATTERN`], making
+    /// accesses to stale allocations easy to recognize.
+    Poison,
+}
+
+/// Byte pattern used by [`ReleasePolicy::Poison`]. Reads as `0xDEADBEEF` when
+/// interpreted as a little-endian `u32`.
+pub const RELEASE_POISON_PATTERN: [u8; 4] = 0xDEADBEEF_u32.to_le_bytes();
+
+impl ReleasePolicy {
+    /// Apply this policy to the `len` bytes at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for writes of `len` bytes, and no references to
+    /// this memory may exist.
+    pub unsafe fn apply(&self, ptr: *mut u8, len: usize) {
+        let pattern = match self {
+            ReleasePolicy::Leave => return,
+            ReleasePolicy::Zeroize => [0; 4],
+            ReleasePolicy::Poison => RELEASE_POISON_PATTERN,
+        };
+
+        // Use volatile writes, such that the compiler does not elide writes to
+        // memory that is about to be released:
+        for offset in 0..len {
+            unsafe { core::ptr::write_volatile(ptr.add(offset), pattern[offset % pattern.len()]) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
 pub unsafe trait EncapfnRt {
     type ID: EFID;
     type AllocTracker<'a>: AllocTracker;
@@ -61,13 +137,56 @@ pub unsafe trait EncapfnRt {
 
     // Can be used to set up memory protection before running the
     // invoke asm. May be implemented as a nop.
-    fn execute<R, F: FnOnce() -> R>(
+    //
+    // Results must implement `EFType`, such that runtimes running foreign
+    // code in a separate address space can validate them before handing them
+    // out to Rust code.
+    fn execute<R: EFType, F: FnOnce() -> R>(
         &self,
         alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
         access_scope: &mut AccessScope<Self::ID>,
         f: F,
     ) -> R;
 
+    /// Like [`execute`](EncapfnRt::execute), but report failures of the
+    /// foreign code as an [`EFError`], where supported by the runtime.
+    ///
+    /// The default implementation never fails.
+    fn try_execute<R: EFType, F: FnOnce() -> R>(
+        &self,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        f: F,
+    ) -> Result<R, EFError> {
+        Ok(self.execute(alloc_scope, access_scope, f))
+    }
+
+    /// Whether this runtime can enforce timeouts in
+    /// [`execute_with_timeout`](EncapfnRt::execute_with_timeout).
+    fn supports_timeout(&self) -> bool {
+        false
+    }
+
+    /// Like [`try_execute`](EncapfnRt::try_execute), but abort the foreign
+    /// code with [`EFError::Timeout`] if it does not return within `timeout`.
+    ///
+    /// How foreign code is aborted is runtime-specific, but runtimes generally
+    /// need to be reset afterwards. Runtimes which can't enforce timeouts (see
+    /// [`supports_timeout`](EncapfnRt::supports_timeout)) return
+    /// [`EFError::Unsupported`] without running `f`, which is the default.
+    fn execute_with_timeout<R: EFType, F: FnOnce() -> R>(
+        &self,
+        _timeout: core::time::Duration,
+        _alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        _access_scope: &mut AccessScope<Self::ID>,
+        _f: F,
+    ) -> Result<R, EFError> {
+        Err(EFError::Unsupported)
+    }
+
+    // Methods allocating memory are `#[track_caller]`, such that allocators
+    // can report the call site of allocations for debugging purposes.
+    #[track_caller]
     fn allocate_stacked_untracked_mut<F, R>(
         &self,
         layout: core::alloc::Layout,
@@ -77,6 +196,7 @@ pub unsafe trait EncapfnRt {
         F: FnOnce(*mut ()) -> R;
 
     // TODO: document layout requirements!
+    #[track_caller]
     fn allocate_stacked_mut<F, R>(
         &self,
         layout: core::alloc::Layout,
@@ -87,6 +207,7 @@ pub unsafe trait EncapfnRt {
         F: for<'b> FnOnce(*mut (), &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>) -> R;
 
     // TODO: what about zero-sized T?
+    #[track_caller]
     fn allocate_stacked_t_mut<T: Sized + 'static, F, R>(
         &self,
         alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
@@ -113,6 +234,41 @@ pub unsafe trait EncapfnRt {
         )
     }
 
+    /// Allocate all allocations declared in `frame` within a single stacked
+    /// allocation, and hand them to the closure.
+    ///
+    /// See [`frame`] for the available declarations.
+    #[track_caller]
+    fn allocate_stacked_frame_mut<S: EFStackedFrame, F, R>(
+        &self,
+        frame: S,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(
+            S::Allocations<'_, Self::ID>,
+            &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        ) -> R,
+    {
+        let layout = frame.layout().ok_or(EFError::AllocInvalidLayout)?;
+
+        // Allocators may not support zero-sized allocations, so always
+        // allocate at least a single byte:
+        let layout =
+            core::alloc::Layout::from_size_align(core::cmp::max(layout.size(), 1), layout.align())
+                .map_err(|_| EFError::AllocInvalidLayout)?;
+
+        let id_imprint = alloc_scope.id_imprint();
+        self.allocate_stacked_mut(layout, alloc_scope, |allocated_ptr, new_alloc_scope| {
+            fun(
+                unsafe { frame.materialize(allocated_ptr as *mut u8, id_imprint) },
+                new_alloc_scope,
+            )
+        })
+    }
+
+    #[track_caller]
     fn write_stacked_t_mut<T: Sized + 'static, F, R>(
         &self,
         t: T,
@@ -133,6 +289,7 @@ pub unsafe trait EncapfnRt {
         })
     }
 
+    #[track_caller]
     fn write_stacked_t<T: Sized + 'static, F, R>(
         &self,
         t: T,
@@ -157,6 +314,7 @@ pub unsafe trait EncapfnRt {
         )
     }
 
+    #[track_caller]
     fn write_stacked_ref_t_mut<T: Sized + Copy + 'static, F, R>(
         &self,
         t: &T,
@@ -177,6 +335,7 @@ pub unsafe trait EncapfnRt {
         })
     }
 
+    #[track_caller]
     fn write_stacked_ref_t<T: Sized + Copy + 'static, F, R>(
         &self,
         t: &T,
@@ -201,7 +360,121 @@ pub unsafe trait EncapfnRt {
         )
     }
 
+    /// Allocate a `T` to be used as an out-parameter of a foreign function.
+    ///
+    /// The closure receives a pointer to this allocation, and is expected to
+    /// perform the foreign call. Once it returns, the allocation is copied and
+    /// returned alongside the closure's return value, ready for validation.
+    #[track_caller]
+    fn allocate_stacked_out_t<T: Sized + 'static, F, R>(
+        &self,
+        init: OutParamInit,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        fun: F,
+    ) -> Result<(R, EFCopy<T>), EFError>
+    where
+        F: for<'b> FnOnce(
+            EFPtr<T>,
+            &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &'b mut AccessScope<Self::ID>,
+        ) -> R,
+    {
+        self.allocate_stacked_t_mut(
+            alloc_scope,
+            |allocation: EFMutRef<'_, Self::ID, T>, new_alloc_scope| {
+                if init == OutParamInit::Zeroed {
+                    // Safety: taking &mut AccessScope<ID> ensures that no other
+                    // references into foreign memory exist. The allocation is
+                    // mutably accessible and well-aligned.
+                    unsafe { core::ptr::write_bytes(allocation.as_ptr().0, 0, 1) };
+                }
+
+                let ret = fun(allocation.as_ptr(), new_alloc_scope, access_scope);
+                (ret, allocation.copy(access_scope))
+            },
+        )
+    }
+
+    /// Fill a `Vec<u8>` through a foreign function which writes into a
+    /// caller-provided `(buf, len)` output buffer.
+    ///
+    /// The closure receives a pointer to, and the length of, a stacked buffer
+    /// of at least `initial_len` bytes, and is expected to perform the foreign
+    /// call. When it reports [`OutBufLen::TooSmall`], it is called again with
+    /// a larger buffer, as it is when the output fills the entire buffer.
+    /// Returns `Ok(None)` when it reports [`OutBufLen::Failed`],
+    /// [`EFError::OutBufTooLarge`] when the required length exceeds `max_len`,
+    /// and [`EFError::OutBufOverrun`] when it reports having written past the
+    /// end of the buffer.
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "alloc")))]
+    #[cfg(any(feature = "alloc", doc))]
+    fn fill_stacked_buf_vec<F>(
+        &self,
+        initial_len: usize,
+        max_len: usize,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        fun: F,
+    ) -> Result<Option<alloc::vec::Vec<u8>>, EFError>
+    where
+        F: FnMut(
+            EFPtr<u8>,
+            usize,
+            &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &mut AccessScope<Self::ID>,
+        ) -> OutBufLen,
+    {
+        fill_stacked_buf(
+            self,
+            initial_len,
+            max_len,
+            alloc_scope,
+            access_scope,
+            fun,
+            |buf, access_scope| buf.validate(access_scope).map(|bytes| bytes.to_vec()),
+        )
+    }
+
+    /// Fill a `String` through a foreign function which writes into a
+    /// caller-provided `(buf, len)` output buffer.
+    ///
+    /// Behaves like [`EncapfnRt::fill_stacked_buf_vec`], but additionally
+    /// returns `Ok(None)` when the written bytes are not valid UTF-8.
+    #[cfg_attr(feature = "nightly", doc(cfg(feature = "alloc")))]
+    #[cfg(any(feature = "alloc", doc))]
+    fn fill_stacked_buf_string<F>(
+        &self,
+        initial_len: usize,
+        max_len: usize,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        fun: F,
+    ) -> Result<Option<alloc::string::String>, EFError>
+    where
+        F: FnMut(
+            EFPtr<u8>,
+            usize,
+            &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &mut AccessScope<Self::ID>,
+        ) -> OutBufLen,
+    {
+        fill_stacked_buf(
+            self,
+            initial_len,
+            max_len,
+            alloc_scope,
+            access_scope,
+            fun,
+            |buf, access_scope| {
+                buf.validate_as_str(access_scope)
+                    .map(|s| alloc::string::String::from(&*s))
+            },
+        )
+    }
+
     // TODO: what about zero-sized T?
+    #[track_caller]
     fn allocate_stacked_slice_mut<T: Sized + 'static, F, R>(
         &self,
         len: usize,
@@ -231,6 +504,7 @@ pub unsafe trait EncapfnRt {
     }
 
     // TODO: what about an empty iterator?
+    #[track_caller]
     fn write_stacked_slice_from_iter_mut<T: Sized + 'static, F, R>(
         &self,
         src: impl Iterator<Item = T> + ExactSizeIterator,
@@ -253,6 +527,7 @@ pub unsafe trait EncapfnRt {
         })
     }
 
+    #[track_caller]
     fn write_stacked_slice_mut<T: Sized + Copy + 'static, F, R>(
         &self,
         src: &[T],
@@ -270,6 +545,7 @@ pub unsafe trait EncapfnRt {
         self.write_stacked_slice_from_iter_mut(src.iter().copied(), alloc_scope, access_scope, fun)
     }
 
+    #[track_caller]
     fn write_stacked_slice_from_iter<T: Sized + 'static, F, R>(
         &self,
         src: impl Iterator<Item = T> + ExactSizeIterator,
@@ -294,6 +570,7 @@ pub unsafe trait EncapfnRt {
         )
     }
 
+    #[track_caller]
     fn write_stacked_slice<T: Sized + Copy + 'static, F, R>(
         &self,
         src: &[T],
@@ -310,4 +587,408 @@ pub unsafe trait EncapfnRt {
     {
         self.write_stacked_slice_from_iter(src.iter().copied(), alloc_scope, access_scope, fun)
     }
+
+    /// Lend a Rust-owned slice to foreign code for the duration of the
+    /// closure.
+    ///
+    /// Runtimes which can make Rust memory accessible to foreign code
+    /// register `src` in the closure's `AllocScope` without copying it. By
+    /// default, `src` is copied into a stacked allocation instead.
+    #[track_caller]
+    fn lend_slice<T: Sized + Copy + 'static, F, R>(
+        &self,
+        src: &[T],
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(
+            EFSlice<'_, Self::ID, T>,
+            &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &'b mut AccessScope<Self::ID>,
+        ) -> R,
+    {
+        self.write_stacked_slice(src, alloc_scope, access_scope, fun)
+    }
+
+    /// Lend a mutable Rust-owned slice to foreign code for the duration of
+    /// the closure.
+    ///
+    /// As foreign code may write arbitrary bytes into this slice, `T` must be
+    /// valid for any bit pattern. Runtimes which can make Rust memory
+    /// accessible to foreign code register `dst` in the closure's
+    /// `AllocScope` without copying it. By default, `dst` is copied into a
+    /// stacked allocation, and copied back once the closure returns.
+    #[track_caller]
+    fn lend_slice_mut<T: Sized + Copy + EFFromBytes + 'static, F, R>(
+        &self,
+        dst: &mut [T],
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(
+            EFMutSlice<'_, Self::ID, T>,
+            &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &'b mut AccessScope<Self::ID>,
+        ) -> R,
+    {
+        self.allocate_stacked_slice_mut(dst.len(), alloc_scope, |allocation, new_alloc_scope| {
+            allocation.write_from_iter(dst.iter().copied(), access_scope);
+
+            let src_ptr = allocation.as_ptr();
+            let ret = fun(allocation, new_alloc_scope, access_scope);
+
+            // Safety: `T: EFFromBytes` ensures that any bit pattern written by
+            // foreign code is a valid `T`. Holding onto &mut AccessScope
+            // ensures that no foreign code is accessing this memory:
+            unsafe {
+                core::ptr::copy_nonoverlapping(src_ptr.0, dst.as_mut_ptr(), dst.len());
+            }
+
+            ret
+        })
+    }
+
+    /// Marshal `value` into a single stacked allocation, and hand an
+    /// `EFSlice` over its root objects to the closure.
+    ///
+    /// See [`EFMarshal`] for how values are laid out. All interior pointers
+    /// point into this allocation, which is tracked by the closure's
+    /// `AllocScope`. The allocation is zeroed before marshalling, such that
+    /// padding bytes and fields not written by [`EFMarshal::marshal_into`]
+    /// are never handed to foreign code uninitialized.
+    #[track_caller]
+    fn write_stacked_marshalled<M: EFMarshal + ?Sized, F, R>(
+        &self,
+        value: &M,
+        alloc_scope: &mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+        access_scope: &mut AccessScope<Self::ID>,
+        fun: F,
+    ) -> Result<R, EFError>
+    where
+        F: for<'b> FnOnce(
+            EFSlice<'_, Self::ID, M::Foreign>,
+            &'b mut AllocScope<'_, Self::AllocTracker<'_>, Self::ID>,
+            &'b mut AccessScope<Self::ID>,
+        ) -> R,
+    {
+        let root_len = value.root_len();
+        let root_size = core::mem::size_of::<M::Foreign>()
+            .checked_mul(root_len)
+            .ok_or(EFError::AllocInvalidLayout)?;
+        let total_size = root_size
+            .checked_add(value.nested_size())
+            .ok_or(EFError::AllocInvalidLayout)?;
+
+        // Allocators may not support zero-sized allocations, so always
+        // allocate at least a single byte:
+        let layout = core::alloc::Layout::from_size_align(
+            core::cmp::max(total_size, 1),
+            core::mem::align_of::<M::Foreign>(),
+        )
+        .map_err(|_| EFError::AllocInvalidLayout)?;
+
+        let id_imprint = alloc_scope.id_imprint();
+        self.allocate_stacked_mut(layout, alloc_scope, |allocated_ptr, new_alloc_scope| {
+            let root = allocated_ptr as *mut M::Foreign;
+
+            // Safety: the allocation is well-aligned for `M::Foreign`, and
+            // large enough to hold all root objects followed by the nested
+            // data. Holding onto `&mut AccessScope` ensures that no other
+            // references into foreign memory exist:
+            unsafe {
+                core::ptr::write_bytes(allocated_ptr as *mut u8, 0, layout.size());
+                let mut arena = EFMarshalArena::new(
+                    (allocated_ptr as *mut u8).add(root_size),
+                    total_size - root_size,
+                );
+                value.marshal_into(root, &mut arena);
+            }
+
+            // Marshalled values are fully written, but not through any of the
+            // `EFMutRef` or `EFMutSlice` methods which track this:
+            #[cfg(feature = "shadow_memory")]
+            crate::shadow::mark_initialized(allocated_ptr as *const u8, total_size);
+
+            fun(
+                unsafe { EFPtr::from(root).upgrade_unchecked_slice(root_len, id_imprint) },
+                new_alloc_scope,
+                access_scope,
+            )
+        })
+    }
+}
+
+#[cfg(any(feature = "alloc", doc))]
+fn fill_stacked_buf<RT, T, F, V>(
+    rt: &RT,
+    initial_len: usize,
+    max_len: usize,
+    alloc_scope: &mut AllocScope<'_, RT::AllocTracker<'_>, RT::ID>,
+    access_scope: &mut AccessScope<RT::ID>,
+    mut fun: F,
+    mut convert: V,
+) -> Result<Option<T>, EFError>
+where
+    RT: EncapfnRt + ?Sized,
+    F: FnMut(
+        EFPtr<u8>,
+        usize,
+        &mut AllocScope<'_, RT::AllocTracker<'_>, RT::ID>,
+        &mut AccessScope<RT::ID>,
+    ) -> OutBufLen,
+    V: FnMut(EFSlice<'_, RT::ID, u8>, &AccessScope<RT::ID>) -> Option<T>,
+{
+    // Allocators may not support zero-sized allocations:
+    let mut len = core::cmp::max(core::cmp::min(initial_len, max_len), 1);
+    if len > max_len {
+        return Err(EFError::OutBufTooLarge { required: None });
+    }
+
+    loop {
+        // Either the converted result, or the length to retry with, if any:
+        let res = rt.allocate_stacked_slice_mut::<u8, _, _>(
+            len,
+            alloc_scope,
+            |buf, inner_alloc_scope| {
+                match fun(buf.as_ptr(), len, inner_alloc_scope, access_scope) {
+                    OutBufLen::Written(written) if written < len => Ok(Ok(convert(
+                        buf.as_immut().get_range(..written).unwrap(),
+                        access_scope,
+                    ))),
+                    OutBufLen::Written(written) if written > len => {
+                        Err(EFError::OutBufOverrun { written, len })
+                    }
+                    OutBufLen::TooSmall(Some(required)) if required > len => {
+                        Ok(Err(Some(required)))
+                    }
+                    // Output filling the entire buffer may have been
+                    // truncated, such as by `snprintf` to make room for the
+                    // NUL terminator:
+                    OutBufLen::Written(_) | OutBufLen::TooSmall(_) => Ok(Err(None)),
+                    OutBufLen::Failed => Ok(Ok(None)),
+                }
+            },
+        )??;
+
+        len = match res {
+            Ok(converted) => return Ok(converted),
+            Err(Some(required)) if required > max_len => {
+                return Err(EFError::OutBufTooLarge {
+                    required: Some(required),
+                })
+            }
+            Err(Some(required)) => required,
+            // Double the buffer, but don't grow it past `max_len`:
+            Err(None) if len < max_len => core::cmp::min(len.saturating_mul(2), max_len),
+            Err(None) => return Err(EFError::OutBufTooLarge { required: None }),
+        };
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_allocate_stacked_out_t() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+
+    // Only initializes the first element of its out-parameter:
+    extern "C" fn get_info(out: *mut [u32; 2]) -> i32 {
+        unsafe { (*out)[0] = 42 };
+        0
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+
+        let (ret, info) = rt
+            .allocate_stacked_out_t::<[u32; 2], _, _>(
+                OutParamInit::Zeroed,
+                &mut alloc_scope,
+                &mut access_scope,
+                |out, alloc_scope, access_scope| {
+                    rt.execute(alloc_scope, access_scope, || get_info(out.0))
+                },
+            )
+            .unwrap();
+        assert_eq!(ret, 0);
+        assert_eq!(info.validate().unwrap(), [42, 0]);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_fill_stacked_buf_string() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+
+    // Writes as much of its output as fits, returning the full length:
+    extern "C" fn get_greeting(buf: *mut u8, len: usize) -> usize {
+        let greeting = b"hello, world";
+        let written = core::cmp::min(len, greeting.len());
+        unsafe { core::ptr::copy_nonoverlapping(greeting.as_ptr(), buf, written) };
+        greeting.len()
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+
+        let mut calls = 0;
+        let greeting = rt
+            .fill_stacked_buf_string(
+                4,
+                64,
+                &mut alloc_scope,
+                &mut access_scope,
+                |buf, len, alloc_scope, access_scope| {
+                    calls += 1;
+                    match rt.execute(alloc_scope, access_scope, || get_greeting(buf.0, len)) {
+                        written if written < len => OutBufLen::Written(written),
+                        required => OutBufLen::TooSmall(Some(required + 1)),
+                    }
+                },
+            )
+            .unwrap();
+        assert_eq!(greeting.as_deref(), Some("hello, world"));
+        assert_eq!(calls, 2);
+
+        assert_eq!(
+            rt.fill_stacked_buf_vec(
+                4,
+                8,
+                &mut alloc_scope,
+                &mut access_scope,
+                |buf, len, alloc_scope, access_scope| {
+                    match rt.execute(alloc_scope, access_scope, || get_greeting(buf.0, len)) {
+                        written if written < len => OutBufLen::Written(written),
+                        required => OutBufLen::TooSmall(Some(required + 1)),
+                    }
+                },
+            ),
+            Err(EFError::OutBufTooLarge { required: Some(13) })
+        );
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_fill_stacked_buf_truncation() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+
+    // Writes as much of its output as fits, returning the length written:
+    extern "C" fn get_greeting(buf: *mut u8, len: usize) -> usize {
+        let greeting = b"hello, world";
+        let written = core::cmp::min(len, greeting.len());
+        unsafe { core::ptr::copy_nonoverlapping(greeting.as_ptr(), buf, written) };
+        written
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+
+        // Output filling the entire buffer is retried with a larger one:
+        let mut lens = std::vec::Vec::new();
+        let greeting = rt
+            .fill_stacked_buf_string(
+                4,
+                64,
+                &mut alloc_scope,
+                &mut access_scope,
+                |buf, len, alloc_scope, access_scope| {
+                    lens.push(len);
+                    OutBufLen::Written(
+                        rt.execute(alloc_scope, access_scope, || get_greeting(buf.0, len)),
+                    )
+                },
+            )
+            .unwrap();
+        assert_eq!(greeting.as_deref(), Some("hello, world"));
+        assert_eq!(lens, [4, 8, 16]);
+
+        // ...unless the buffer can't grow any further. The required length is
+        // then unknown:
+        assert_eq!(
+            rt.fill_stacked_buf_vec(
+                4,
+                12,
+                &mut alloc_scope,
+                &mut access_scope,
+                |buf, len, alloc_scope, access_scope| {
+                    OutBufLen::Written(
+                        rt.execute(alloc_scope, access_scope, || get_greeting(buf.0, len)),
+                    )
+                },
+            ),
+            Err(EFError::OutBufTooLarge { required: None })
+        );
+        assert_eq!(
+            rt.fill_stacked_buf_vec(4, 12, &mut alloc_scope, &mut access_scope, |_, _, _, _| {
+                OutBufLen::TooSmall(None)
+            },),
+            Err(EFError::OutBufTooLarge { required: None })
+        );
+
+        // Reports of writing past the end of the buffer are not retried:
+        let mut calls = 0;
+        assert_eq!(
+            rt.fill_stacked_buf_vec(4, 64, &mut alloc_scope, &mut access_scope, |_, _, _, _| {
+                calls += 1;
+                OutBufLen::Written(5)
+            },),
+            Err(EFError::OutBufOverrun { written: 5, len: 4 })
+        );
+        assert_eq!(calls, 1);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_lend_slice_mut() {
+    use crate::branding::EFLifetimeBranding;
+    use crate::rt::mock::heap_alloc::HeapAllocator;
+    use crate::rt::mock::MockRt;
+
+    extern "C" fn increment_all(buf: *mut u32, len: usize) {
+        for i in 0..len {
+            unsafe { *buf.add(i) += 1 };
+        }
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let (rt, mut alloc_scope, mut access_scope) =
+            unsafe { MockRt::new(false, false, HeapAllocator, brand) };
+
+        let mut values = [1_u32, 2, 3];
+        let values_ptr = values.as_mut_ptr();
+        rt.lend_slice_mut(
+            &mut values,
+            &mut alloc_scope,
+            &mut access_scope,
+            |lent, alloc_scope, access_scope| {
+                // The MockRt lends Rust memory without copying it:
+                assert_eq!(lent.as_ptr().0, values_ptr);
+                assert!(lent.as_ptr().upgrade_slice_mut(3, alloc_scope).is_some());
+                rt.execute(alloc_scope, access_scope, || {
+                    increment_all(lent.as_ptr().0, lent.len())
+                });
+            },
+        )
+        .unwrap();
+        assert_eq!(values, [2, 3, 4]);
+
+        // The registration is revoked once the closure returns:
+        assert!(EFPtr::from(values_ptr)
+            .upgrade_slice(3, &alloc_scope)
+            .is_none());
+    });
 }
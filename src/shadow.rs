@@ -0,0 +1,177 @@
+//! Shadow-memory tracking of uninitialized bytes in foreign memory.
+//!
+//! Runtimes can register their allocations along with an initialization
+//! bitmap, holding one bit per byte of the allocation. Writes through
+//! [`EFMutRef`](crate::types::EFMutRef), [`EFMutSlice`](crate::types::EFMutSlice)
+//! and their unaligned counterparts mark bytes as initialized, whereas
+//! validating or copying bytes that were never initialized panics.
+//!
+//! Writes performed by foreign code can't be observed directly. Instead,
+//! runtimes fill registered allocations with [`SHADOW_POISON`], and any byte
+//! that no longer holds this value is considered to be written by foreign
+//! code. Foreign code writing this exact value is thus not detected, and may
+//! cause spurious reports. The same holds for padding bytes of types written
+//! by foreign code, unless it writes the entire value at once.
+//!
+//! Registered regions are stored in a global table of fixed size. Like the
+//! `MockRt`, this assumes that allocations are registered and checked from a
+//! single thread at a time.
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Byte value that registered allocations are initially filled with.
+pub const SHADOW_POISON: u8 = 0xA5;
+
+/// Maximum number of simultaneously registered regions.
+pub const SHADOW_REGIONS: usize = 64;
+
+#[derive(Clone, Copy)]
+struct ShadowRegion {
+    ptr: usize,
+    len: usize,
+    bitmap: *mut u8,
+}
+
+struct ShadowTable {
+    lock: AtomicBool,
+    regions: UnsafeCell<[Option<ShadowRegion>; SHADOW_REGIONS]>,
+}
+
+// Access to `regions` is guarded by `lock`:
+unsafe impl Sync for ShadowTable {}
+
+static SHADOW_TABLE: ShadowTable = ShadowTable {
+    lock: AtomicBool::new(false),
+    regions: UnsafeCell::new([None; SHADOW_REGIONS]),
+};
+
+fn with_regions<R>(f: impl FnOnce(&mut [Option<ShadowRegion>; SHADOW_REGIONS]) -> R) -> R {
+    while SHADOW_TABLE
+        .lock
+        .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        core::hint::spin_loop();
+    }
+
+    // Callers must not panic within `f`, as that would leave the table
+    // locked:
+    let res = f(unsafe { &mut *SHADOW_TABLE.regions.get() });
+    SHADOW_TABLE.lock.store(false, Ordering::Release);
+    res
+}
+
+/// Number of bytes of the initialization bitmap of a region of `len` bytes.
+pub const fn bitmap_len(len: usize) -> usize {
+    len.div_ceil(8)
+}
+
+/// Register a region of foreign memory for shadow-memory tracking, filling it
+/// with [`SHADOW_POISON`] and marking all of its bytes as uninitialized.
+///
+/// Returns `false` if [`SHADOW_REGIONS`] regions are already registered.
+///
+/// # Safety
+///
+/// `ptr` must be valid for writes of `len` bytes, and `bitmap` must be valid
+/// for writes of [`bitmap_len(len)`](bitmap_len) bytes, until the region is
+/// unregistered through [`unregister_region`].
+pub unsafe fn register_region(ptr: *mut u8, len: usize, bitmap: *mut u8) -> bool {
+    unsafe {
+        core::ptr::write_bytes(ptr, SHADOW_POISON, len);
+        core::ptr::write_bytes(bitmap, 0, bitmap_len(len));
+    }
+
+    with_regions(|regions| match regions.iter_mut().find(|r| r.is_none()) {
+        Some(slot) => {
+            *slot = Some(ShadowRegion {
+                ptr: ptr as usize,
+                len,
+                bitmap,
+            });
+            true
+        }
+        None => false,
+    })
+}
+
+/// Unregister a region previously registered at `ptr`.
+pub fn unregister_region(ptr: *mut u8) {
+    with_regions(|regions| {
+        if let Some(slot) = regions
+            .iter_mut()
+            .find(|r| r.is_some_and(|r| r.ptr == ptr as usize))
+        {
+            *slot = None;
+        }
+    })
+}
+
+// Invoke `f` with every registered region overlapping `[ptr, ptr + len)`, and
+// the range of overlapping offsets within this region:
+fn for_overlapping_regions(
+    ptr: *const u8,
+    len: usize,
+    mut f: impl FnMut(&ShadowRegion, core::ops::Range<usize>),
+) {
+    let start = ptr as usize;
+    let end = start.saturating_add(len);
+
+    with_regions(|regions| {
+        for region in regions.iter().flatten() {
+            let overlap_start = core::cmp::max(start, region.ptr);
+            let overlap_end = core::cmp::min(end, region.ptr + region.len);
+            if overlap_start < overlap_end {
+                f(
+                    region,
+                    (overlap_start - region.ptr)..(overlap_end - region.ptr),
+                );
+            }
+        }
+    })
+}
+
+/// Mark the `len` bytes at `ptr` as initialized.
+pub fn mark_initialized(ptr: *const u8, len: usize) {
+    for_overlapping_regions(ptr, len, |region, offsets| {
+        for offset in offsets {
+            unsafe { *region.bitmap.add(offset / 8) |= 1 << (offset % 8) };
+        }
+    })
+}
+
+/// Panic if any of the `len` bytes at `ptr` was neither written through Rust,
+/// nor (presumably) by foreign code.
+pub fn check_initialized(ptr: *const u8, len: usize) {
+    let mut uninit = None;
+
+    for_overlapping_regions(ptr, len, |region, offsets| {
+        if uninit.is_some() {
+            return;
+        }
+
+        uninit = offsets
+            .clone()
+            .find(|offset| {
+                let marked = unsafe { *region.bitmap.add(offset / 8) } & (1 << (offset % 8)) != 0;
+                // Use volatile reads, as foreign code may have modified this
+                // memory behind the compiler's back:
+                let byte =
+                    unsafe { core::ptr::read_volatile((region.ptr as *const u8).add(*offset)) };
+                !marked && byte == SHADOW_POISON
+            })
+            .map(|offset| (region.ptr, region.len, offset));
+    });
+
+    if let Some((region_ptr, region_len, offset)) = uninit {
+        panic!(
+            "Read of uninitialized foreign memory at {:#x}: byte {} of the {}-byte allocation \
+             at {:#x} was never written!",
+            region_ptr + offset,
+            offset,
+            region_len,
+            region_ptr,
+        );
+    }
+}
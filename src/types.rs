@@ -1,7 +1,8 @@
-use core::cell::UnsafeCell;
+use core::any::TypeId;
+use core::cell::{Cell, UnsafeCell};
 use core::marker::PhantomData;
 use core::mem::MaybeUninit;
-use core::ops::Deref;
+use core::ops::{Bound, Deref, RangeBounds};
 
 use crate::branding::EFID;
 use crate::util::maybe_uninit_as_bytes;
@@ -10,6 +11,20 @@ use crate::util::maybe_uninit_as_bytes;
 const DISABLE_UPGRADE_CHECKS: bool = cfg!(feature = "disable_upgrade_checks");
 const DISABLE_VALIDATION_CHECKS: bool = cfg!(feature = "disable_validation_checks");
 
+// Shadow-memory hooks, which compile to no-ops unless the `shadow_memory`
+// feature is enabled. See `crate::shadow` for details.
+#[inline(always)]
+fn shadow_mark_initialized(_ptr: *const (), _len: usize) {
+    #[cfg(feature = "shadow_memory")]
+    crate::shadow::mark_initialized(_ptr as *const u8, _len);
+}
+
+#[inline(always)]
+fn shadow_check_initialized(_ptr: *const (), _len: usize) {
+    #[cfg(feature = "shadow_memory")]
+    crate::shadow::check_initialized(_ptr as *const u8, _len);
+}
+
 pub unsafe trait AllocTracker {
     fn is_valid(&self, ptr: *const (), len: usize) -> bool;
     fn is_valid_mut(&self, ptr: *mut (), len: usize) -> bool;
@@ -58,7 +73,456 @@ impl<ID: EFID> AccessScope<ID> {
 }
 
 pub unsafe trait EFType {
+    /// Whether every instance of this type is valid, regardless of its bit
+    /// pattern. When set, validation of this type, and of arrays and slices
+    /// of it, is skipped entirely.
+    ///
+    /// Implementations of compound types should set this to the conjunction
+    /// of their members' values. Setting it to `true` requires that
+    /// [`EFType::validate`] unconditionally returns `true`.
+    const ALWAYS_VALID: bool = false;
+
     unsafe fn validate(t: *const Self) -> bool;
+
+    /// Validate `t`, reporting where and why validation failed.
+    ///
+    /// The default implementation defers to [`EFType::validate`], and reports
+    /// the entire value as invalid. Compound types should override this to
+    /// report the offending member through [`EFValidationError::within_field`]
+    /// or [`EFValidationError::within_index`].
+    ///
+    /// # Safety
+    ///
+    /// Same requirements as for [`EFType::validate`]. Implementations must
+    /// return `Ok(())` if and only if [`EFType::validate`] returns `true`.
+    unsafe fn validate_detailed(t: *const Self) -> Result<(), EFValidationError>
+    where
+        Self: Sized,
+    {
+        if <Self as EFType>::validate(t) {
+            Ok(())
+        } else {
+            Err(EFValidationError::new(t))
+        }
+    }
+}
+
+/// Maximum number of bytes of an invalid value retained in an
+/// [`EFValidationError`].
+pub const EF_VALIDATION_ERROR_BYTES: usize = 16;
+
+/// Maximum length of the textual field path retained in an
+/// [`EFValidationError`].
+pub const EF_VALIDATION_ERROR_PATH_LEN: usize = 48;
+
+/// Detailed report of a failed validation.
+///
+/// This does not allocate, and thus retains only a bounded number of bytes of
+/// the invalid value and of its path (such as `.items[3].flag`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EFValidationError {
+    type_name: &'static str,
+    byte_offset: usize,
+    // The path is built back-to-front while unwinding from the invalid value,
+    // and occupies `path[path_start..]`:
+    path: [u8; EF_VALIDATION_ERROR_PATH_LEN],
+    path_start: u8,
+    path_truncated: bool,
+    bytes: [u8; EF_VALIDATION_ERROR_BYTES],
+    bytes_len: u8,
+}
+
+impl EFValidationError {
+    /// Construct a new error for an invalid value of type `T`.
+    ///
+    /// As values of `T` may contain padding or otherwise uninitialized bytes,
+    /// this does not retain any bytes of the invalid value. Use
+    /// [`EFValidationError::new_with_bytes`] for types without such bytes.
+    pub fn new<T>(_t: *const T) -> Self {
+        EFValidationError {
+            type_name: core::any::type_name::<T>(),
+            byte_offset: 0,
+            path: [0; EF_VALIDATION_ERROR_PATH_LEN],
+            path_start: EF_VALIDATION_ERROR_PATH_LEN as u8,
+            path_truncated: false,
+            bytes: [0; EF_VALIDATION_ERROR_BYTES],
+            bytes_len: 0,
+        }
+    }
+
+    /// Construct a new error for an invalid value of type `T`, retaining its
+    /// leading bytes.
+    ///
+    /// # Safety
+    ///
+    /// `t` must point to `size_of::<T>()` bytes of readable, initialized
+    /// memory.
+    pub unsafe fn new_with_bytes<T: EFAsBytes>(t: *const T) -> Self {
+        let mut err = Self::new(t);
+        let bytes_len = core::cmp::min(core::mem::size_of::<T>(), EF_VALIDATION_ERROR_BYTES);
+        core::ptr::copy_nonoverlapping(t as *const u8, err.bytes.as_mut_ptr(), bytes_len);
+        err.bytes_len = bytes_len as u8;
+        err
+    }
+
+    fn prepend_segment(mut self, segment: &[&[u8]], offset: usize) -> Self {
+        self.byte_offset += offset;
+
+        // Once we dropped a segment, don't add any outer ones, such that the
+        // path always forms a contiguous suffix:
+        let len: usize = segment.iter().map(|part| part.len()).sum();
+        if self.path_truncated || len > self.path_start as usize {
+            self.path_truncated = true;
+            return self;
+        }
+
+        let mut pos = self.path_start as usize - len;
+        self.path_start = pos as u8;
+        for part in segment {
+            self.path[pos..pos + part.len()].copy_from_slice(part);
+            pos += part.len();
+        }
+
+        self
+    }
+
+    /// Record that this error occurred in the member `field`, located at
+    /// `offset` bytes into its enclosing value.
+    pub fn within_field(self, field: &'static str, offset: usize) -> Self {
+        self.prepend_segment(&[b".", field.as_bytes()], offset)
+    }
+
+    /// Record that this error occurred in the element at `index` of an array
+    /// or slice, located at `offset` bytes into its enclosing value.
+    pub fn within_index(self, index: usize, offset: usize) -> Self {
+        // Format the index in decimal, back-to-front:
+        let mut digits = [0_u8; 20];
+        let mut start = digits.len();
+        let mut remaining = index;
+        loop {
+            start -= 1;
+            digits[start] = b'0' + (remaining % 10) as u8;
+            remaining /= 10;
+            if remaining == 0 {
+                break;
+            }
+        }
+
+        self.prepend_segment(&[b"[", &digits[start..], b"]"], offset)
+    }
+
+    /// Name of the innermost type which failed validation.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Offset of the invalid value, relative to the start of the value that
+    /// validation was requested for.
+    pub fn byte_offset(&self) -> usize {
+        self.byte_offset
+    }
+
+    /// Path from the outermost value to the invalid value, such as
+    /// `.items[3].flag`.
+    pub fn path(&self) -> &str {
+        // We only ever insert entire segments composed of `str`s and ASCII
+        // characters, so this is always valid UTF-8:
+        core::str::from_utf8(&self.path[self.path_start as usize..]).unwrap_or("")
+    }
+
+    /// Whether outer path segments were dropped, as the path exceeded
+    /// [`EF_VALIDATION_ERROR_PATH_LEN`] bytes.
+    pub fn path_truncated(&self) -> bool {
+        self.path_truncated
+    }
+
+    /// The (leading) bytes of the invalid value. Empty unless this error was
+    /// constructed through [`EFValidationError::new_with_bytes`].
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes[..self.bytes_len as usize]
+    }
+}
+
+impl core::fmt::Display for EFValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "invalid value of type `{}` at byte offset {}",
+            self.type_name, self.byte_offset
+        )?;
+
+        if !self.path().is_empty() {
+            write!(
+                f,
+                ", path {}{}",
+                if self.path_truncated { "..." } else { "" },
+                self.path()
+            )?;
+        }
+
+        if self.bytes().is_empty() {
+            return Ok(());
+        }
+
+        f.write_str(", bytes [")?;
+        for (i, byte) in self.bytes().iter().enumerate() {
+            if i != 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+        f.write_str("]")
+    }
+}
+
+/// Marker trait for types for which every initialized byte pattern of
+/// `size_of::<Self>()` bytes is a valid instance.
+///
+/// This allows reinterpreting foreign byte buffers as references to such
+/// types, for instance through [`EFSlice::try_cast_ref`]. Implementors will
+/// generally want to set [`EFType::ALWAYS_VALID`] as well.
+///
+/// # Safety
+///
+/// Implementors must guarantee that [`EFType::validate`] returns `true` for
+/// every fully initialized value, and that the type does not contain any
+/// interior mutability.
+pub unsafe trait EFFromBytes: EFType {}
+
+/// Marker trait for types which do not contain any padding bytes, and thus
+/// every valid instance is fully initialized.
+///
+/// This allows viewing references to such types as byte slices, for instance
+/// through [`EFRef::as_bytes`].
+///
+/// # Safety
+///
+/// Implementors must guarantee that the type does not contain any padding or
+/// otherwise uninitialized bytes, and that it does not contain any interior
+/// mutability.
+pub unsafe trait EFAsBytes: EFType {}
+
+/// Validation of a type, including all foreign memory reachable through
+/// pointers contained in it.
+///
+/// [`EFType::validate`] only inspects the bytes of a value itself. Types that
+/// contain pointers into foreign memory can implement this trait to
+/// additionally require that these pointers can be upgraded through the
+/// runtime's [`AllocTracker`], and that their pointees are (transitively)
+/// valid. This turns validating a foreign linked list or tree into a single,
+/// checked operation.
+///
+/// Types without any embedded pointers can rely on the default
+/// implementation, which defers to [`EFType::validate`]. Types with pointer
+/// members should validate themselves through [`EFType::validate`] and then
+/// recurse into every pointer through [`EFDeepValidationCtx::validate_ptr`].
+///
+/// # Safety
+///
+/// Implementations must only return `true` if [`EFType::validate`] would
+/// return `true` for the same value.
+pub unsafe trait EFTypeDeep: EFType {
+    /// Whether every instance of this type is deeply valid, regardless of its
+    /// bit pattern. When set, deep validation of this type, and of arrays and
+    /// slices of it, is skipped entirely.
+    ///
+    /// Unlike [`EFType::ALWAYS_VALID`], this must not be set for types
+    /// containing pointers which are followed, such as [`EFPtr`]. Setting it
+    /// to `true` requires that [`EFTypeDeep::validate_deep`] unconditionally
+    /// returns `true`.
+    const ALWAYS_VALID_DEEP: bool = false;
+
+    /// # Safety
+    ///
+    /// `t` must be well-aligned and point to accessible memory of
+    /// `size_of::<Self>()` bytes, which must not be modified for the duration
+    /// of this call.
+    unsafe fn validate_deep<R: AllocTracker>(
+        t: *const Self,
+        _ctx: &EFDeepValidationCtx<'_, R>,
+    ) -> bool {
+        <Self as EFType>::ALWAYS_VALID || <Self as EFType>::validate(t)
+    }
+}
+
+/// Context for a transitive validation through [`EFTypeDeep`].
+///
+/// This holds onto the [`AllocTracker`] used to check embedded pointers, the
+/// remaining budget of pointer dereferences, the chain of objects currently
+/// being validated, and a cache of objects which have already been validated.
+/// All of this state lives on the stack.
+pub struct EFDeepValidationCtx<'a, R: AllocTracker> {
+    tracker: &'a R,
+    depth_budget: usize,
+    node: EFDeepValidationNode,
+    parent: Option<&'a EFDeepValidationCtx<'a, R>>,
+    visited: &'a EFDeepValidationVisited,
+}
+
+/// Number of validated objects remembered during a single transitive
+/// validation, see [`EFDeepValidationCtx::validate_ptr`].
+const EF_DEEP_VALIDATION_VISITED_ENTRIES: usize = 32;
+
+// An object is identified by both its address and type, as objects of
+// different types may share an address (e.g., a struct and its first field):
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct EFDeepValidationNode {
+    ptr: *const (),
+    type_id: TypeId,
+}
+
+impl EFDeepValidationNode {
+    fn new<T: 'static>(ptr: *const T) -> Self {
+        EFDeepValidationNode {
+            ptr: ptr as *const (),
+            type_id: TypeId::of::<T>(),
+        }
+    }
+}
+
+// Ring buffer of the most recently validated objects:
+struct EFDeepValidationVisited {
+    entries: [Cell<Option<EFDeepValidationNode>>; EF_DEEP_VALIDATION_VISITED_ENTRIES],
+    next: Cell<usize>,
+}
+
+impl EFDeepValidationVisited {
+    fn new() -> Self {
+        EFDeepValidationVisited {
+            entries: [const { Cell::new(None) }; EF_DEEP_VALIDATION_VISITED_ENTRIES],
+            next: Cell::new(0),
+        }
+    }
+
+    fn contains(&self, node: EFDeepValidationNode) -> bool {
+        self.entries.iter().any(|entry| entry.get() == Some(node))
+    }
+
+    fn insert(&self, node: EFDeepValidationNode) {
+        let idx = self.next.get();
+        self.entries[idx].set(Some(node));
+        self.next
+            .set((idx + 1) % EF_DEEP_VALIDATION_VISITED_ENTRIES);
+    }
+}
+
+impl<'a, R: AllocTracker> EFDeepValidationCtx<'a, R> {
+    fn new<T: 'static>(
+        tracker: &'a R,
+        depth_budget: usize,
+        root: *const T,
+        visited: &'a EFDeepValidationVisited,
+    ) -> Self {
+        EFDeepValidationCtx {
+            tracker,
+            depth_budget,
+            node: EFDeepValidationNode::new(root),
+            parent: None,
+            visited,
+        }
+    }
+
+    pub fn tracker(&self) -> &R {
+        self.tracker
+    }
+
+    pub fn depth_budget(&self) -> usize {
+        self.depth_budget
+    }
+
+    fn is_ancestor(&self, node: EFDeepValidationNode) -> bool {
+        let mut cur = Some(self);
+        while let Some(ctx) = cur {
+            if ctx.node == node {
+                return true;
+            }
+            cur = ctx.parent;
+        }
+        false
+    }
+
+    /// Validate the foreign object pointed to by `ptr`, and everything
+    /// reachable from it.
+    ///
+    /// Null pointers are considered valid. Pointers to objects of type `T`
+    /// that are currently being validated further up the chain (i.e., cycles)
+    /// are considered valid as well, as their validity is established by the
+    /// enclosing invocation. All other pointers must be well-aligned and
+    /// accessible through the [`AllocTracker`], and their pointee must be
+    /// valid according to [`EFTypeDeep`]. Validation fails once the depth
+    /// budget is exhausted.
+    ///
+    /// Objects reachable through multiple paths are validated only once, as
+    /// long as they are among the most recently validated objects.
+    ///
+    /// # Safety
+    ///
+    /// The caller must hold an [`AccessScope`] for the duration of this call,
+    /// such that foreign memory is not modified concurrently.
+    pub unsafe fn validate_ptr<T: EFTypeDeep + 'static>(&self, ptr: *const T) -> bool {
+        let node = EFDeepValidationNode::new(ptr);
+        if ptr.is_null() || self.is_ancestor(node) || self.visited.contains(node) {
+            return true;
+        }
+
+        if self.depth_budget == 0 {
+            return false;
+        }
+
+        let accessible = ptr.is_aligned()
+            && self
+                .tracker
+                .is_valid(ptr as *const (), core::mem::size_of::<T>());
+        if !DISABLE_UPGRADE_CHECKS && !accessible {
+            return false;
+        }
+
+        shadow_check_initialized(ptr as *const (), core::mem::size_of::<T>());
+
+        // This branch is resolved at compile time:
+        if <T as EFTypeDeep>::ALWAYS_VALID_DEEP {
+            return true;
+        }
+
+        let child = EFDeepValidationCtx {
+            tracker: self.tracker,
+            depth_budget: self.depth_budget - 1,
+            node,
+            parent: Some(self),
+            visited: self.visited,
+        };
+
+        // Validation as a whole fails as soon as any object is invalid, so we
+        // only need to remember successfully validated objects. This holds
+        // even for objects whose validity assumed that of an ancestor:
+        let valid = <T as EFTypeDeep>::validate_deep(ptr, &child);
+        if valid {
+            self.visited.insert(node);
+        }
+        valid
+    }
+}
+
+/// Resolve a `RangeBounds<usize>` into a `start..end` pair for a slice of
+/// length `len`, returning `None` if the range is out of bounds or inverted.
+fn resolve_range<R: RangeBounds<usize>>(range: R, len: usize) -> Option<(usize, usize)> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1)?,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if start <= end && end <= len {
+        Some((start, end))
+    } else {
+        None
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -278,6 +742,142 @@ impl<T: 'static> EFPtr<T> {
             }
         }
     }
+
+    /// # Safety
+    ///
+    /// The pointed-to memory must be accessible for the lifetime `'alloc`.
+    pub unsafe fn upgrade_unchecked_unaligned<'alloc, ID: EFID>(
+        &self,
+        id_imprint: ID::Imprint,
+    ) -> EFUnalignedRef<'alloc, ID, T> {
+        EFUnalignedRef {
+            ptr: self.0,
+            id_imprint,
+            _alloc_lt: PhantomData,
+        }
+    }
+
+    /// Upgrade this pointer into a reference to a potentially unaligned `T`.
+    ///
+    /// In contrast to [`EFPtr::upgrade`], this does not check that the pointer
+    /// is well-aligned for `T`. The returned reference only supports accesses
+    /// through unaligned, byte-wise copies.
+    pub fn upgrade_unaligned<'alloc, R: AllocTracker, ID: EFID>(
+        &self,
+        alloc_scope: &AllocScope<'alloc, R, ID>,
+    ) -> Option<EFUnalignedRef<'alloc, ID, T>> {
+        if DISABLE_UPGRADE_CHECKS
+            || alloc_scope
+                .tracker()
+                .is_valid(self.0 as *const (), core::mem::size_of::<T>())
+        {
+            Some(unsafe { self.upgrade_unchecked_unaligned(alloc_scope.id_imprint()) })
+        } else {
+            None
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The pointed-to memory must be mutably accessible for the lifetime
+    /// `'alloc`.
+    pub unsafe fn upgrade_unchecked_unaligned_mut<'alloc, ID: EFID>(
+        &self,
+        id_imprint: ID::Imprint,
+    ) -> EFUnalignedMutRef<'alloc, ID, T> {
+        EFUnalignedMutRef {
+            ptr: self.0,
+            id_imprint,
+            _alloc_lt: PhantomData,
+        }
+    }
+
+    pub fn upgrade_unaligned_mut<'alloc, R: AllocTracker, ID: EFID>(
+        &self,
+        alloc_scope: &AllocScope<'alloc, R, ID>,
+    ) -> Option<EFUnalignedMutRef<'alloc, ID, T>> {
+        if DISABLE_UPGRADE_CHECKS
+            || alloc_scope
+                .tracker()
+                .is_valid_mut(self.0 as *mut (), core::mem::size_of::<T>())
+        {
+            Some(unsafe { self.upgrade_unchecked_unaligned_mut(alloc_scope.id_imprint()) })
+        } else {
+            None
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The pointed-to memory of `length` elements must be accessible for the
+    /// lifetime `'alloc`.
+    pub unsafe fn upgrade_unchecked_unaligned_slice<'alloc, ID: EFID>(
+        &self,
+        length: usize,
+        id_imprint: ID::Imprint,
+    ) -> EFUnalignedSlice<'alloc, ID, T> {
+        EFUnalignedSlice {
+            ptr: self.0,
+            len: length,
+            id_imprint,
+            _alloc_lt: PhantomData,
+        }
+    }
+
+    pub fn upgrade_unaligned_slice<'alloc, R: AllocTracker, ID: EFID>(
+        &self,
+        length: usize,
+        alloc_scope: &AllocScope<'alloc, R, ID>,
+    ) -> Option<EFUnalignedSlice<'alloc, ID, T>> {
+        if DISABLE_UPGRADE_CHECKS
+            || length
+                .checked_mul(core::mem::size_of::<T>())
+                .map(|len| alloc_scope.tracker().is_valid(self.0 as *const (), len))
+                .unwrap_or(false)
+        {
+            Some(unsafe {
+                self.upgrade_unchecked_unaligned_slice(length, alloc_scope.id_imprint())
+            })
+        } else {
+            None
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The pointed-to memory of `length` elements must be mutably accessible
+    /// for the lifetime `'alloc`.
+    pub unsafe fn upgrade_unchecked_unaligned_slice_mut<'alloc, ID: EFID>(
+        &self,
+        length: usize,
+        id_imprint: ID::Imprint,
+    ) -> EFUnalignedMutSlice<'alloc, ID, T> {
+        EFUnalignedMutSlice {
+            ptr: self.0,
+            len: length,
+            id_imprint,
+            _alloc_lt: PhantomData,
+        }
+    }
+
+    pub fn upgrade_unaligned_slice_mut<'alloc, R: AllocTracker, ID: EFID>(
+        &self,
+        length: usize,
+        alloc_scope: &AllocScope<'alloc, R, ID>,
+    ) -> Option<EFUnalignedMutSlice<'alloc, ID, T>> {
+        if DISABLE_UPGRADE_CHECKS
+            || length
+                .checked_mul(core::mem::size_of::<T>())
+                .map(|len| alloc_scope.tracker().is_valid_mut(self.0 as *mut (), len))
+                .unwrap_or(false)
+        {
+            Some(unsafe {
+                self.upgrade_unchecked_unaligned_slice_mut(length, alloc_scope.id_imprint())
+            })
+        } else {
+            None
+        }
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -341,6 +941,8 @@ impl<T: 'static> EFCopy<T> {
             );
         }
 
+        shadow_check_initialized(r.r.get() as *const (), core::mem::size_of::<T>());
+
         // Safety: taking &AccessScope<ID> ensures that no mutable accessible
         // references into foreign memory exist, and that no foreign code is
         // accessing this memory. The existance of this type ensures that this
@@ -376,7 +978,7 @@ impl<T: 'static> EFCopy<T> {
 
 impl<T: EFType + 'static> EFCopy<T> {
     pub fn validate(self) -> Result<T, Self> {
-        if DISABLE_VALIDATION_CHECKS {
+        if DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID {
             Ok(unsafe { self.assume_valid() })
         } else {
             if unsafe { <T as EFType>::validate(&self.0 as *const MaybeUninit<T> as *const T) } {
@@ -388,7 +990,7 @@ impl<T: EFType + 'static> EFCopy<T> {
     }
 
     pub fn validate_ref<'a>(&'a self) -> Option<&'a T> {
-        if DISABLE_VALIDATION_CHECKS {
+        if DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID {
             Some(unsafe { self.assume_valid_ref() })
         } else {
             if unsafe { <T as EFType>::validate(&self.0 as *const MaybeUninit<T> as *const T) } {
@@ -399,6 +1001,20 @@ impl<T: EFType + 'static> EFCopy<T> {
         }
     }
 
+    /// Like `validate`, but report why validation failed.
+    pub fn validate_detailed(self) -> Result<T, (Self, EFValidationError)> {
+        if DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID {
+            return Ok(unsafe { self.assume_valid() });
+        }
+
+        match unsafe {
+            <T as EFType>::validate_detailed(&self.0 as *const MaybeUninit<T> as *const T)
+        } {
+            Ok(()) => Ok(unsafe { self.0.assume_init() }),
+            Err(e) => Err((self, e)),
+        }
+    }
+
     pub fn validate_copy(&self) -> Option<T> {
         // TODO: maybe more efficient to validate ref first, then clone:
         let cloned = self.clone();
@@ -442,7 +1058,9 @@ impl<'alloc, ID: EFID, T: EFType + 'static> EFMutRef<'alloc, ID, T> {
             );
         }
 
-        if DISABLE_VALIDATION_CHECKS {
+        shadow_check_initialized(self.r.get() as *const (), core::mem::size_of::<T>());
+
+        if DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID {
             Some(unsafe { self.assume_valid(access_scope) })
         } else {
             if unsafe {
@@ -454,6 +1072,32 @@ impl<'alloc, ID: EFID, T: EFType + 'static> EFMutRef<'alloc, ID, T> {
             }
         }
     }
+
+    /// Like `validate`, but report why validation failed.
+    pub fn validate_detailed<'access>(
+        &self,
+        access_scope: &'access AccessScope<ID>,
+    ) -> Result<EFVal<'alloc, 'access, ID, T>, EFValidationError> {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_check_initialized(self.r.get() as *const (), core::mem::size_of::<T>());
+
+        if !(DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID) {
+            unsafe {
+                <T as EFType>::validate_detailed(
+                    self.r as *const UnsafeCell<MaybeUninit<T>> as *const T,
+                )
+            }?;
+        }
+
+        Ok(unsafe { self.assume_valid(access_scope) })
+    }
 }
 
 impl<'alloc, ID: EFID, T: 'static> EFMutRef<'alloc, ID, T> {
@@ -513,6 +1157,8 @@ impl<'alloc, ID: EFID, T: 'static> EFMutRef<'alloc, ID, T> {
             );
         }
 
+        shadow_mark_initialized(self.r.get() as *const (), core::mem::size_of::<T>());
+
         // Safety: taking &mut AccessScope<ID> ensures that no other accessible
         // references into foreign memory exist, and that no foreign code is
         // accessing this memory. The existance of this type ensures that this
@@ -538,6 +1184,8 @@ impl<'alloc, ID: EFID, T: 'static> EFMutRef<'alloc, ID, T> {
             );
         }
 
+        shadow_mark_initialized(self.r.get() as *const (), core::mem::size_of::<T>());
+
         // Safety: taking &mut AccessScope<ID> ensures that no other accessible
         // references into foreign memory exist, and that no foreign code is
         // accessing this memory. The existance of this type ensures that this
@@ -578,6 +1226,8 @@ impl<'alloc, ID: EFID, T: Copy + 'static> EFMutRef<'alloc, ID, T> {
             );
         }
 
+        shadow_mark_initialized(self.r.get() as *const (), core::mem::size_of::<T>());
+
         // Safety: taking &mut AccessScope<ID> ensures that no other accessible
         // references into foreign memory exist, and that no foreign code is
         // accessing this memory. The existance of this type ensures that this
@@ -595,6 +1245,20 @@ impl<'alloc, ID: EFID, T: Copy + 'static> EFMutRef<'alloc, ID, T> {
     }
 }
 
+impl<'alloc, ID: EFID, T: EFFromBytes + EFAsBytes + 'static> EFMutRef<'alloc, ID, T> {
+    pub fn as_bytes_mut(&self) -> EFMutSlice<'alloc, ID, u8> {
+        EFMutSlice {
+            r: unsafe {
+                core::slice::from_raw_parts(
+                    self.r as *const _ as *const UnsafeCell<MaybeUninit<u8>>,
+                    core::mem::size_of::<T>(),
+                )
+            },
+            id_imprint: self.id_imprint,
+        }
+    }
+}
+
 impl<'alloc, const N: usize, ID: EFID, T: 'static> EFMutRef<'alloc, ID, [T; N]> {
     pub fn len(&self) -> usize {
         N
@@ -718,6 +1382,105 @@ impl<'alloc, ID: EFID, T: 'static> EFMutSlice<'alloc, ID, T> {
         self.r.len()
     }
 
+    pub fn get(&self, idx: usize) -> Option<EFMutRef<'alloc, ID, T>> {
+        self.r.get(idx).map(|elem| EFMutRef {
+            r: elem,
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    pub fn iter(&self) -> EFMutSliceIter<'alloc, ID, T> {
+        EFMutSliceIter {
+            inner: self.r.iter(),
+            id_imprint: self.id_imprint,
+        }
+    }
+
+    /// Return a sub-slice of this slice, or `None` if `range` is out of
+    /// bounds.
+    pub fn get_range<R: RangeBounds<usize>>(&self, range: R) -> Option<EFMutSlice<'alloc, ID, T>> {
+        let (start, end) = resolve_range(range, self.r.len())?;
+        Some(EFMutSlice {
+            r: &self.r[start..end],
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    /// Divide this slice into two at index `mid`, or return `None` if `mid >
+    /// len`.
+    ///
+    /// As all accesses to the underlying memory are mediated through an
+    /// [`AccessScope`], it is fine for the returned slices to overlap with
+    /// `self`.
+    pub fn split_at(
+        &self,
+        mid: usize,
+    ) -> Option<(EFMutSlice<'alloc, ID, T>, EFMutSlice<'alloc, ID, T>)> {
+        if mid > self.r.len() {
+            return None;
+        }
+
+        let (head, tail) = self.r.split_at(mid);
+        Some((
+            EFMutSlice {
+                r: head,
+                id_imprint: self.id_imprint,
+            },
+            EFMutSlice {
+                r: tail,
+                id_imprint: self.id_imprint,
+            },
+        ))
+    }
+
+    pub fn split_first(&self) -> Option<(EFMutRef<'alloc, ID, T>, EFMutSlice<'alloc, ID, T>)> {
+        self.r.split_first().map(|(first, rest)| {
+            (
+                EFMutRef {
+                    r: first,
+                    id_imprint: self.id_imprint,
+                },
+                EFMutSlice {
+                    r: rest,
+                    id_imprint: self.id_imprint,
+                },
+            )
+        })
+    }
+
+    /// Iterate over `chunk_size` elements of this slice at a time. The last
+    /// chunk may be shorter than `chunk_size`.
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn chunks(&self, chunk_size: usize) -> EFMutSliceChunks<'alloc, ID, T> {
+        EFMutSliceChunks {
+            inner: self.r.chunks(chunk_size),
+            id_imprint: self.id_imprint,
+        }
+    }
+
+    /// Iterate over `chunk_size` elements of this slice at a time. Trailing
+    /// elements that do not fill an entire chunk are available through
+    /// [`EFMutSliceChunksExact::remainder`].
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn chunks_exact(&self, chunk_size: usize) -> EFMutSliceChunksExact<'alloc, ID, T> {
+        EFMutSliceChunksExact {
+            inner: self.r.chunks_exact(chunk_size),
+            id_imprint: self.id_imprint,
+        }
+    }
+
+    /// Iterate over all overlapping windows of length `size`.
+    ///
+    /// Panics if `size` is zero.
+    pub fn windows(&self, size: usize) -> EFMutSliceWindows<'alloc, ID, T> {
+        EFMutSliceWindows {
+            inner: self.r.windows(size),
+            id_imprint: self.id_imprint,
+        }
+    }
+
     pub fn write_from_iter<'access, I: Iterator<Item = T>>(
         &self,
         src: I,
@@ -740,6 +1503,12 @@ impl<'alloc, ID: EFID, T: 'static> EFMutSlice<'alloc, ID, T> {
             (unsafe { &mut *dst.get() }).write(val);
             count += 1;
         });
+
+        // Only mark the elements actually written:
+        shadow_mark_initialized(
+            self.r.as_ptr() as *const (),
+            core::mem::size_of::<T>() * count,
+        );
         assert!(count == self.r.len());
 
         // Provide a validated reference to the newly written memory, bound to
@@ -748,9 +1517,150 @@ impl<'alloc, ID: EFID, T: 'static> EFMutSlice<'alloc, ID, T> {
         // instance of T, and we copied self.r.len() elements from `src`:
         unsafe { self.assume_valid(access_scope) }
     }
-}
 
-impl<'alloc, ID: EFID, T: EFType + 'static> EFMutSlice<'alloc, ID, T> {
+    /// Copy all elements from `src` into this slice.
+    ///
+    /// Both slices may reside anywhere in foreign memory, and are permitted
+    /// to overlap. This performs a raw, byte-wise copy and thus does not
+    /// require `T` to be validated. Panics if the slices differ in length.
+    pub fn copy_from_efslice(&self, src: &EFSlice<'_, ID, T>, access_scope: &mut AccessScope<ID>) {
+        if self.id_imprint != access_scope.id_imprint() || src.id_imprint != self.id_imprint {
+            panic!(
+                "ID mismatch: {:?} vs. {:?} vs. {:?}!",
+                self.id_imprint,
+                src.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        if src.r.len() != self.r.len() {
+            panic!(
+                "Called EFMutSlice::copy_from_efslice with a source slice of length {}, destination slice has length {}",
+                src.r.len(),
+                self.r.len(),
+            );
+        }
+
+        shadow_check_initialized(src.r.as_ptr() as *const (), core::mem::size_of_val(src.r));
+
+        // Safety: taking &mut AccessScope<ID> ensures that no other accessible
+        // references into foreign memory exist, and that no foreign code is
+        // accessing this memory. The existance of these types ensures that
+        // `src` is accessible, `self` is mutably accessible, and both are
+        // well-aligned. `ptr::copy` has `memmove` semantics, and hence permits
+        // overlapping regions:
+        unsafe {
+            core::ptr::copy(
+                src.r.as_ptr() as *const MaybeUninit<T>,
+                self.r.as_ptr() as *mut MaybeUninit<T>,
+                self.r.len(),
+            )
+        }
+
+        shadow_mark_initialized(self.r.as_ptr() as *const (), core::mem::size_of_val(self.r));
+    }
+
+    /// Copy the elements in `src` to the position starting at `dest`, within
+    /// this slice. The source and destination ranges may overlap.
+    ///
+    /// Panics if either range exceeds the end of the slice.
+    pub fn copy_within<R: RangeBounds<usize>>(
+        &self,
+        src: R,
+        dest: usize,
+        access_scope: &mut AccessScope<ID>,
+    ) {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        let Some((src_start, src_end)) = resolve_range(src, self.r.len()) else {
+            panic!(
+                "Called EFMutSlice::copy_within with a source range out of bounds for slice of length {}",
+                self.r.len(),
+            );
+        };
+
+        let count = src_end - src_start;
+        if dest > self.r.len() - count {
+            panic!(
+                "Called EFMutSlice::copy_within with destination {} and {} elements, out of bounds for slice of length {}",
+                dest,
+                count,
+                self.r.len(),
+            );
+        }
+
+        let base = self.r.as_ptr() as *mut MaybeUninit<T>;
+        let elem_size = core::mem::size_of::<T>();
+        shadow_check_initialized(
+            unsafe { base.add(src_start) } as *const (),
+            count * elem_size,
+        );
+
+        // Safety: see `copy_from_efslice`. We've checked that both the
+        // source and destination ranges are in bounds:
+        unsafe { core::ptr::copy(base.add(src_start), base.add(dest), count) }
+
+        shadow_mark_initialized(unsafe { base.add(dest) } as *const (), count * elem_size);
+    }
+
+    /// Swap all elements of this slice with those of `other`.
+    ///
+    /// Swapping a slice with itself is a no-op. Panics if the slices differ
+    /// in length, or if they partially overlap.
+    pub fn swap_with(&self, other: &EFMutSlice<'_, ID, T>, access_scope: &mut AccessScope<ID>) {
+        if self.id_imprint != access_scope.id_imprint() || other.id_imprint != self.id_imprint {
+            panic!(
+                "ID mismatch: {:?} vs. {:?} vs. {:?}!",
+                self.id_imprint,
+                other.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        if other.r.len() != self.r.len() {
+            panic!(
+                "Called EFMutSlice::swap_with with a slice of length {}, own length {}",
+                other.r.len(),
+                self.r.len(),
+            );
+        }
+
+        let self_start = self.r.as_ptr() as usize;
+        let other_start = other.r.as_ptr() as usize;
+        let byte_len = core::mem::size_of_val(self.r);
+
+        if self_start == other_start {
+            // Swapping with ourselves, nothing to do:
+            return;
+        }
+
+        if self_start < other_start + byte_len && other_start < self_start + byte_len {
+            panic!("Called EFMutSlice::swap_with with partially overlapping slices");
+        }
+
+        // Both slices are read, and then written with each other's contents:
+        shadow_check_initialized(self_start as *const (), byte_len);
+        shadow_check_initialized(other_start as *const (), byte_len);
+
+        // Safety: see `copy_from_efslice`. We've ensured that both slices
+        // have the same length and do not overlap:
+        unsafe {
+            core::ptr::swap_nonoverlapping(
+                self.r.as_ptr() as *mut MaybeUninit<T>,
+                other.r.as_ptr() as *mut MaybeUninit<T>,
+                self.r.len(),
+            )
+        }
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFType + 'static> EFMutSlice<'alloc, ID, T> {
     pub fn validate<'access>(
         &self,
         access_scope: &'access AccessScope<ID>,
@@ -763,7 +1673,9 @@ impl<'alloc, ID: EFID, T: EFType + 'static> EFMutSlice<'alloc, ID, T> {
             );
         }
 
-        if DISABLE_VALIDATION_CHECKS {
+        shadow_check_initialized(self.r.as_ptr() as *const (), core::mem::size_of_val(self.r));
+
+        if DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID {
             Some(unsafe { self.assume_valid(access_scope) })
         } else {
             if self
@@ -779,6 +1691,38 @@ impl<'alloc, ID: EFID, T: EFType + 'static> EFMutSlice<'alloc, ID, T> {
             }
         }
     }
+
+    /// Like `validate`, but report the first invalid element, and why it
+    /// failed validation.
+    pub fn validate_detailed<'access>(
+        &self,
+        access_scope: &'access AccessScope<ID>,
+    ) -> Result<EFSliceVal<'alloc, 'access, ID, T>, EFValidationError> {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_check_initialized(self.r.as_ptr() as *const (), core::mem::size_of_val(self.r));
+
+        if !(DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID) {
+            self.r.iter().enumerate().try_for_each(
+                |(idx, elem): (usize, &UnsafeCell<MaybeUninit<T>>)| {
+                    unsafe {
+                        <T as EFType>::validate_detailed(
+                            elem as *const UnsafeCell<MaybeUninit<T>> as *const T,
+                        )
+                    }
+                    .map_err(|e| e.within_index(idx, idx * core::mem::size_of::<T>()))
+                },
+            )?;
+        }
+
+        Ok(unsafe { self.assume_valid(access_scope) })
+    }
 }
 
 impl<'alloc, ID: EFID, T: Copy + 'static> EFMutSlice<'alloc, ID, T> {
@@ -789,6 +1733,169 @@ impl<'alloc, ID: EFID, T: Copy + 'static> EFMutSlice<'alloc, ID, T> {
     ) -> EFSliceVal<'alloc, 'access, ID, T> {
         self.write_from_iter(src.iter().copied(), access_scope)
     }
+
+    pub fn fill<'access>(
+        &self,
+        val: T,
+        access_scope: &'access mut AccessScope<ID>,
+    ) -> EFSliceVal<'alloc, 'access, ID, T> {
+        self.write_from_iter(core::iter::repeat_n(val, self.r.len()), access_scope)
+    }
+}
+
+impl<'alloc, ID: EFID> EFMutSlice<'alloc, ID, u8> {
+    /// Reinterpret this byte slice as a mutable reference to a `T`.
+    ///
+    /// Returns `None` if the slice is not well-aligned for `T`, or its length
+    /// is not exactly `size_of::<T>()`. `T` must not contain padding, as
+    /// writing a `T` would otherwise de-initialize bytes of this slice.
+    pub fn try_cast_mut<T: EFFromBytes + EFAsBytes + 'static>(
+        &self,
+    ) -> Option<EFMutRef<'alloc, ID, T>> {
+        if self.r.len() != core::mem::size_of::<T>() || !(self.r.as_ptr() as *const T).is_aligned()
+        {
+            return None;
+        }
+
+        Some(EFMutRef {
+            r: unsafe { &*(self.r.as_ptr() as *const UnsafeCell<MaybeUninit<T>>) },
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    /// Mutable equivalent of [`EFSlice::try_cast_ref_prefix`].
+    pub fn try_cast_mut_prefix<T: EFFromBytes + EFAsBytes + 'static>(
+        &self,
+    ) -> Option<(EFMutRef<'alloc, ID, T>, EFMutSlice<'alloc, ID, u8>)> {
+        let (head, tail) = self.split_at(core::mem::size_of::<T>())?;
+        Some((head.try_cast_mut()?, tail))
+    }
+
+    /// Mutable equivalent of [`EFSlice::try_cast_slice`].
+    pub fn try_cast_slice_mut<T: EFFromBytes + EFAsBytes + 'static>(
+        &self,
+    ) -> Option<EFMutSlice<'alloc, ID, T>> {
+        let size = core::mem::size_of::<T>();
+        if size == 0
+            || !self.r.len().is_multiple_of(size)
+            || !(self.r.as_ptr() as *const T).is_aligned()
+        {
+            return None;
+        }
+
+        Some(EFMutSlice {
+            r: unsafe {
+                core::slice::from_raw_parts(
+                    self.r.as_ptr() as *const UnsafeCell<MaybeUninit<T>>,
+                    self.r.len() / size,
+                )
+            },
+            id_imprint: self.id_imprint,
+        })
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFFromBytes + EFAsBytes + 'static> EFMutSlice<'alloc, ID, T> {
+    pub fn as_bytes_mut(&self) -> EFMutSlice<'alloc, ID, u8> {
+        EFMutSlice {
+            r: unsafe {
+                core::slice::from_raw_parts(
+                    self.r.as_ptr() as *const UnsafeCell<MaybeUninit<u8>>,
+                    core::mem::size_of_val(self.r),
+                )
+            },
+            id_imprint: self.id_imprint,
+        }
+    }
+}
+
+pub struct EFMutSliceIter<'alloc, ID: EFID, T: 'static> {
+    inner: core::slice::Iter<'alloc, UnsafeCell<MaybeUninit<T>>>,
+    id_imprint: ID::Imprint,
+}
+
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFMutSliceIter<'alloc, ID, T> {
+    type Item = EFMutRef<'alloc, ID, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|r| EFMutRef {
+            r,
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pub struct EFMutSliceChunks<'alloc, ID: EFID, T: 'static> {
+    inner: core::slice::Chunks<'alloc, UnsafeCell<MaybeUninit<T>>>,
+    id_imprint: ID::Imprint,
+}
+
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFMutSliceChunks<'alloc, ID, T> {
+    type Item = EFMutSlice<'alloc, ID, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|r| EFMutSlice {
+            r,
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pub struct EFMutSliceChunksExact<'alloc, ID: EFID, T: 'static> {
+    inner: core::slice::ChunksExact<'alloc, UnsafeCell<MaybeUninit<T>>>,
+    id_imprint: ID::Imprint,
+}
+
+impl<'alloc, ID: EFID, T: 'static> EFMutSliceChunksExact<'alloc, ID, T> {
+    pub fn remainder(&self) -> EFMutSlice<'alloc, ID, T> {
+        EFMutSlice {
+            r: self.inner.remainder(),
+            id_imprint: self.id_imprint,
+        }
+    }
+}
+
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFMutSliceChunksExact<'alloc, ID, T> {
+    type Item = EFMutSlice<'alloc, ID, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|r| EFMutSlice {
+            r,
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pub struct EFMutSliceWindows<'alloc, ID: EFID, T: 'static> {
+    inner: core::slice::Windows<'alloc, UnsafeCell<MaybeUninit<T>>>,
+    id_imprint: ID::Imprint,
+}
+
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFMutSliceWindows<'alloc, ID, T> {
+    type Item = EFMutSlice<'alloc, ID, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|r| EFMutSlice {
+            r,
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -836,7 +1943,9 @@ impl<'alloc, ID: EFID, T: EFType + 'static> EFRef<'alloc, ID, T> {
             );
         }
 
-        if DISABLE_VALIDATION_CHECKS {
+        shadow_check_initialized(self.r.get() as *const (), core::mem::size_of::<T>());
+
+        if DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID {
             Some(unsafe { self.assume_valid(access_scope) })
         } else {
             if unsafe {
@@ -848,6 +1957,32 @@ impl<'alloc, ID: EFID, T: EFType + 'static> EFRef<'alloc, ID, T> {
             }
         }
     }
+
+    /// Like `validate`, but report why validation failed.
+    pub fn validate_detailed<'access>(
+        &self,
+        access_scope: &'access AccessScope<ID>,
+    ) -> Result<EFVal<'alloc, 'access, ID, T>, EFValidationError> {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_check_initialized(self.r.get() as *const (), core::mem::size_of::<T>());
+
+        if !(DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID) {
+            unsafe {
+                <T as EFType>::validate_detailed(
+                    self.r as *const UnsafeCell<MaybeUninit<T>> as *const T,
+                )
+            }?;
+        }
+
+        Ok(unsafe { self.assume_valid(access_scope) })
+    }
 }
 
 impl<'alloc, ID: EFID, T: 'static> EFRef<'alloc, ID, T> {
@@ -892,6 +2027,60 @@ impl<'alloc, ID: EFID, T: 'static> EFRef<'alloc, ID, T> {
     }
 }
 
+impl<'alloc, ID: EFID, T: EFTypeDeep + 'static> EFRef<'alloc, ID, T> {
+    /// Validate this reference, and all foreign memory reachable from it
+    /// through embedded pointers. See [`EFTypeDeep`] for details.
+    ///
+    /// At most `depth_budget` pointers are followed in sequence. Validation
+    /// of structures that are more deeply nested fails.
+    pub fn validate_deep<'access, R: AllocTracker>(
+        &self,
+        alloc_scope: &AllocScope<'_, R, ID>,
+        access_scope: &'access AccessScope<ID>,
+        depth_budget: usize,
+    ) -> Option<EFVal<'alloc, 'access, ID, T>> {
+        if self.id_imprint != access_scope.id_imprint()
+            || self.id_imprint != alloc_scope.id_imprint()
+        {
+            panic!(
+                "ID mismatch: {:?} vs. {:?} vs. {:?}!",
+                self.id_imprint,
+                alloc_scope.id_imprint(),
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_check_initialized(self.r.get() as *const (), core::mem::size_of::<T>());
+
+        if DISABLE_VALIDATION_CHECKS || <T as EFTypeDeep>::ALWAYS_VALID_DEEP {
+            return Some(unsafe { self.assume_valid(access_scope) });
+        }
+
+        let ptr = self.r as *const UnsafeCell<MaybeUninit<T>> as *const T;
+        let visited = EFDeepValidationVisited::new();
+        let ctx = EFDeepValidationCtx::new(alloc_scope.tracker(), depth_budget, ptr, &visited);
+        if unsafe { <T as EFTypeDeep>::validate_deep(ptr, &ctx) } {
+            Some(unsafe { self.assume_valid(access_scope) })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFAsBytes + 'static> EFRef<'alloc, ID, T> {
+    pub fn as_bytes(&self) -> EFSlice<'alloc, ID, u8> {
+        EFSlice {
+            r: unsafe {
+                core::slice::from_raw_parts(
+                    self.r as *const _ as *const UnsafeCell<MaybeUninit<u8>>,
+                    core::mem::size_of::<T>(),
+                )
+            },
+            id_imprint: self.id_imprint,
+        }
+    }
+}
+
 impl<'alloc, const N: usize, ID: EFID, T: 'static> EFRef<'alloc, ID, [T; N]> {
     pub fn len(&self) -> usize {
         N
@@ -1015,6 +2204,84 @@ impl<'alloc, ID: EFID, T: 'static> EFSlice<'alloc, ID, T> {
             idx: 0,
         }
     }
+
+    /// Return a sub-slice of this slice, or `None` if `range` is out of
+    /// bounds.
+    pub fn get_range<R: RangeBounds<usize>>(&self, range: R) -> Option<EFSlice<'alloc, ID, T>> {
+        let (start, end) = resolve_range(range, self.r.len())?;
+        Some(EFSlice {
+            r: &self.r[start..end],
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    /// Divide this slice into two at index `mid`, or return `None` if `mid >
+    /// len`.
+    pub fn split_at(&self, mid: usize) -> Option<(EFSlice<'alloc, ID, T>, EFSlice<'alloc, ID, T>)> {
+        if mid > self.r.len() {
+            return None;
+        }
+
+        let (head, tail) = self.r.split_at(mid);
+        Some((
+            EFSlice {
+                r: head,
+                id_imprint: self.id_imprint,
+            },
+            EFSlice {
+                r: tail,
+                id_imprint: self.id_imprint,
+            },
+        ))
+    }
+
+    pub fn split_first(&self) -> Option<(EFRef<'alloc, ID, T>, EFSlice<'alloc, ID, T>)> {
+        self.r.split_first().map(|(first, rest)| {
+            (
+                EFRef {
+                    r: first,
+                    id_imprint: self.id_imprint,
+                },
+                EFSlice {
+                    r: rest,
+                    id_imprint: self.id_imprint,
+                },
+            )
+        })
+    }
+
+    /// Iterate over `chunk_size` elements of this slice at a time. The last
+    /// chunk may be shorter than `chunk_size`.
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn chunks(&self, chunk_size: usize) -> EFSliceChunks<'alloc, ID, T> {
+        EFSliceChunks {
+            inner: self.r.chunks(chunk_size),
+            id_imprint: self.id_imprint,
+        }
+    }
+
+    /// Iterate over `chunk_size` elements of this slice at a time. Trailing
+    /// elements that do not fill an entire chunk are available through
+    /// [`EFSliceChunksExact::remainder`].
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn chunks_exact(&self, chunk_size: usize) -> EFSliceChunksExact<'alloc, ID, T> {
+        EFSliceChunksExact {
+            inner: self.r.chunks_exact(chunk_size),
+            id_imprint: self.id_imprint,
+        }
+    }
+
+    /// Iterate over all overlapping windows of length `size`.
+    ///
+    /// Panics if `size` is zero.
+    pub fn windows(&self, size: usize) -> EFSliceWindows<'alloc, ID, T> {
+        EFSliceWindows {
+            inner: self.r.windows(size),
+            id_imprint: self.id_imprint,
+        }
+    }
 }
 
 impl<'alloc, ID: EFID, T: EFType + 'static> EFSlice<'alloc, ID, T> {
@@ -1030,7 +2297,9 @@ impl<'alloc, ID: EFID, T: EFType + 'static> EFSlice<'alloc, ID, T> {
             );
         }
 
-        if DISABLE_VALIDATION_CHECKS {
+        shadow_check_initialized(self.r.as_ptr() as *const (), core::mem::size_of_val(self.r));
+
+        if DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID {
             Some(unsafe { self.assume_valid(access_scope) })
         } else {
             if self
@@ -1046,13 +2315,13 @@ impl<'alloc, ID: EFID, T: EFType + 'static> EFSlice<'alloc, ID, T> {
             }
         }
     }
-}
 
-impl<'alloc, ID: EFID> EFSlice<'alloc, ID, u8> {
-    pub fn validate_as_str<'access>(
+    /// Like `validate`, but report the first invalid element, and why it
+    /// failed validation.
+    pub fn validate_detailed<'access>(
         &self,
         access_scope: &'access AccessScope<ID>,
-    ) -> Option<EFVal<'alloc, 'access, ID, str>> {
+    ) -> Result<EFSliceVal<'alloc, 'access, ID, T>, EFValidationError> {
         if self.id_imprint != access_scope.id_imprint() {
             panic!(
                 "ID mismatch: {:?} vs. {:?}!",
@@ -1061,33 +2330,669 @@ impl<'alloc, ID: EFID> EFSlice<'alloc, ID, u8> {
             );
         }
 
-        if DISABLE_VALIDATION_CHECKS {
-            Some(EFVal {
+        shadow_check_initialized(self.r.as_ptr() as *const (), core::mem::size_of_val(self.r));
+
+        if !(DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID) {
+            self.r.iter().enumerate().try_for_each(
+                |(idx, elem): (usize, &UnsafeCell<MaybeUninit<T>>)| {
+                    unsafe {
+                        <T as EFType>::validate_detailed(
+                            elem as *const UnsafeCell<MaybeUninit<T>> as *const T,
+                        )
+                    }
+                    .map_err(|e| e.within_index(idx, idx * core::mem::size_of::<T>()))
+                },
+            )?;
+        }
+
+        Ok(unsafe { self.assume_valid(access_scope) })
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFTypeDeep + 'static> EFSlice<'alloc, ID, T> {
+    /// Validate every element of this slice, and all foreign memory
+    /// reachable from them. See [`EFRef::validate_deep`].
+    pub fn validate_deep<'access, R: AllocTracker>(
+        &self,
+        alloc_scope: &AllocScope<'_, R, ID>,
+        access_scope: &'access AccessScope<ID>,
+        depth_budget: usize,
+    ) -> Option<EFSliceVal<'alloc, 'access, ID, T>> {
+        if self.id_imprint != access_scope.id_imprint()
+            || self.id_imprint != alloc_scope.id_imprint()
+        {
+            panic!(
+                "ID mismatch: {:?} vs. {:?} vs. {:?}!",
+                self.id_imprint,
+                alloc_scope.id_imprint(),
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_check_initialized(self.r.as_ptr() as *const (), core::mem::size_of_val(self.r));
+
+        if DISABLE_VALIDATION_CHECKS || <T as EFTypeDeep>::ALWAYS_VALID_DEEP {
+            return Some(unsafe { self.assume_valid(access_scope) });
+        }
+
+        // Elements may share pointees, so remember validated objects across
+        // all elements:
+        let visited = EFDeepValidationVisited::new();
+        if self.r.iter().all(|elem: &UnsafeCell<MaybeUninit<T>>| {
+            let ptr = elem as *const UnsafeCell<MaybeUninit<T>> as *const T;
+            let ctx = EFDeepValidationCtx::new(alloc_scope.tracker(), depth_budget, ptr, &visited);
+            unsafe { <T as EFTypeDeep>::validate_deep(ptr, &ctx) }
+        }) {
+            Some(unsafe { self.assume_valid(access_scope) })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFAsBytes + 'static> EFSlice<'alloc, ID, T> {
+    pub fn as_bytes(&self) -> EFSlice<'alloc, ID, u8> {
+        EFSlice {
+            r: unsafe {
+                core::slice::from_raw_parts(
+                    self.r.as_ptr() as *const UnsafeCell<MaybeUninit<u8>>,
+                    core::mem::size_of_val(self.r),
+                )
+            },
+            id_imprint: self.id_imprint,
+        }
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFType + 'static> EFSlice<'alloc, ID, T> {
+    /// Validate and return a reference to a single element of the slice.
+    ///
+    /// Callers must hold an `AccessScope` for the lifetime of the returned
+    /// reference, and must have checked that the imprint matches.
+    unsafe fn validate_elem<'access>(
+        elem: &'alloc UnsafeCell<MaybeUninit<T>>,
+        _access_scope: &'access AccessScope<ID>,
+    ) -> Option<&'access T> {
+        let ptr = elem as *const UnsafeCell<MaybeUninit<T>> as *const T;
+        shadow_check_initialized(ptr as *const (), core::mem::size_of::<T>());
+        if DISABLE_VALIDATION_CHECKS || <T as EFType>::ALWAYS_VALID || <T as EFType>::validate(ptr)
+        {
+            Some(&*ptr)
+        } else {
+            None
+        }
+    }
+
+    /// Return the index of the first element matching `predicate`.
+    ///
+    /// Elements are validated one at a time, as they are visited. Elements
+    /// which fail validation never match.
+    pub fn position<P: FnMut(&T) -> bool>(
+        &self,
+        mut predicate: P,
+        access_scope: &AccessScope<ID>,
+    ) -> Option<usize> {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        self.r.iter().position(|elem| {
+            unsafe { Self::validate_elem(elem, access_scope) }
+                .map(&mut predicate)
+                .unwrap_or(false)
+        })
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFType + PartialEq + 'static> EFSlice<'alloc, ID, T> {
+    /// Compare the elements of this slice with those in `other`.
+    ///
+    /// Elements are validated one at a time, and comparison stops at the first
+    /// difference. Elements which fail validation compare unequal.
+    pub fn eq_slice(&self, other: &[T], access_scope: &AccessScope<ID>) -> bool {
+        self.r.len() == other.len() && self.starts_with(other, access_scope)
+    }
+
+    pub fn starts_with(&self, needle: &[T], access_scope: &AccessScope<ID>) -> bool {
+        self.get_range(..needle.len())
+            .map(|head| head.eq_prefix(needle, access_scope))
+            .unwrap_or(false)
+    }
+
+    pub fn ends_with(&self, needle: &[T], access_scope: &AccessScope<ID>) -> bool {
+        self.r
+            .len()
+            .checked_sub(needle.len())
+            .and_then(|start| self.get_range(start..))
+            .map(|tail| tail.eq_prefix(needle, access_scope))
+            .unwrap_or(false)
+    }
+
+    fn eq_prefix(&self, needle: &[T], access_scope: &AccessScope<ID>) -> bool {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        self.r.iter().zip(needle.iter()).all(|(elem, expected)| {
+            unsafe { Self::validate_elem(elem, access_scope) }
+                .map(|elem| elem == expected)
+                .unwrap_or(false)
+        })
+    }
+}
+
+impl<'alloc, ID: EFID> EFSlice<'alloc, ID, u8> {
+    /// Reinterpret this byte slice as a reference to a `T`.
+    ///
+    /// Returns `None` if the slice is not well-aligned for `T`, or its length
+    /// is not exactly `size_of::<T>()`.
+    pub fn try_cast_ref<T: EFFromBytes + 'static>(&self) -> Option<EFRef<'alloc, ID, T>> {
+        if self.r.len() != core::mem::size_of::<T>() || !(self.r.as_ptr() as *const T).is_aligned()
+        {
+            return None;
+        }
+
+        Some(EFRef {
+            r: unsafe { &*(self.r.as_ptr() as *const UnsafeCell<MaybeUninit<T>>) },
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    /// Reinterpret the first `size_of::<T>()` bytes of this slice as a
+    /// reference to a `T`, and return it alongside the remaining bytes.
+    ///
+    /// Returns `None` if the slice is not well-aligned for `T`, or shorter
+    /// than `size_of::<T>()`.
+    pub fn try_cast_ref_prefix<T: EFFromBytes + 'static>(
+        &self,
+    ) -> Option<(EFRef<'alloc, ID, T>, EFSlice<'alloc, ID, u8>)> {
+        let (head, tail) = self.split_at(core::mem::size_of::<T>())?;
+        Some((head.try_cast_ref()?, tail))
+    }
+
+    /// Reinterpret this byte slice as a slice of `T`s.
+    ///
+    /// Returns `None` if the slice is not well-aligned for `T`, if its length
+    /// is not a multiple of `size_of::<T>()`, or if `T` is zero-sized.
+    pub fn try_cast_slice<T: EFFromBytes + 'static>(&self) -> Option<EFSlice<'alloc, ID, T>> {
+        let size = core::mem::size_of::<T>();
+        if size == 0
+            || !self.r.len().is_multiple_of(size)
+            || !(self.r.as_ptr() as *const T).is_aligned()
+        {
+            return None;
+        }
+
+        Some(EFSlice {
+            r: unsafe {
+                core::slice::from_raw_parts(
+                    self.r.as_ptr() as *const UnsafeCell<MaybeUninit<T>>,
+                    self.r.len() / size,
+                )
+            },
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    /// Return the index of the first occurence of `byte` in this slice.
+    pub fn find_byte(&self, byte: u8, access_scope: &AccessScope<ID>) -> Option<usize> {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        // We rely on the fact that u8s are unconditionally valid, and we hold
+        // onto an AccessScope here:
+        let pos = unsafe { &*(self.r as *const _ as *const [u8]) }
+            .iter()
+            .position(|b| *b == byte);
+
+        // Only the bytes up to and including the match have been inspected:
+        let scanned = pos.map(|pos| pos + 1).unwrap_or(self.r.len());
+        shadow_check_initialized(self.r.as_ptr() as *const (), scanned);
+
+        pos
+    }
+
+    pub fn validate_as_str<'access>(
+        &self,
+        access_scope: &'access AccessScope<ID>,
+    ) -> Option<EFVal<'alloc, 'access, ID, str>> {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_check_initialized(self.r.as_ptr() as *const (), core::mem::size_of_val(self.r));
+
+        if DISABLE_VALIDATION_CHECKS {
+            Some(EFVal {
                 r: unsafe { core::str::from_utf8_unchecked(&*(self.r as *const _ as *const [u8])) },
                 id_imprint: self.id_imprint,
                 _alloc_lt: PhantomData,
             })
         } else {
-            // We rely on the fact that u8s are unconditionally valid, and we
-            // hold onto an AccessScope here
-            core::str::from_utf8(unsafe { &*(self.r as *const _ as *const [u8]) })
-                .ok()
-                .map(|s| EFVal {
-                    r: s,
-                    id_imprint: self.id_imprint,
-                    _alloc_lt: PhantomData,
-                })
+            // We rely on the fact that u8s are unconditionally valid, and we
+            // hold onto an AccessScope here
+            core::str::from_utf8(unsafe { &*(self.r as *const _ as *const [u8]) })
+                .ok()
+                .map(|s| EFVal {
+                    r: s,
+                    id_imprint: self.id_imprint,
+                    _alloc_lt: PhantomData,
+                })
+        }
+    }
+}
+
+pub struct EFSliceIter<'alloc, ID: EFID, T: 'static> {
+    inner: EFSlice<'alloc, ID, T>,
+    idx: usize,
+}
+
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFSliceIter<'alloc, ID, T> {
+    type Item = EFRef<'alloc, ID, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.inner.get(self.idx) {
+            // Prevent wraparound by calling .iter() a bunch.
+            self.idx += 1;
+            Some(item)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct EFSliceChunks<'alloc, ID: EFID, T: 'static> {
+    inner: core::slice::Chunks<'alloc, UnsafeCell<MaybeUninit<T>>>,
+    id_imprint: ID::Imprint,
+}
+
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFSliceChunks<'alloc, ID, T> {
+    type Item = EFSlice<'alloc, ID, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|r| EFSlice {
+            r,
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pub struct EFSliceChunksExact<'alloc, ID: EFID, T: 'static> {
+    inner: core::slice::ChunksExact<'alloc, UnsafeCell<MaybeUninit<T>>>,
+    id_imprint: ID::Imprint,
+}
+
+impl<'alloc, ID: EFID, T: 'static> EFSliceChunksExact<'alloc, ID, T> {
+    pub fn remainder(&self) -> EFSlice<'alloc, ID, T> {
+        EFSlice {
+            r: self.inner.remainder(),
+            id_imprint: self.id_imprint,
+        }
+    }
+}
+
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFSliceChunksExact<'alloc, ID, T> {
+    type Item = EFSlice<'alloc, ID, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|r| EFSlice {
+            r,
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pub struct EFSliceWindows<'alloc, ID: EFID, T: 'static> {
+    inner: core::slice::Windows<'alloc, UnsafeCell<MaybeUninit<T>>>,
+    id_imprint: ID::Imprint,
+}
+
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFSliceWindows<'alloc, ID, T> {
+    type Item = EFSlice<'alloc, ID, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|r| EFSlice {
+            r,
+            id_imprint: self.id_imprint,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+pub struct EFVal<'alloc, 'access, ID: EFID, T: 'static + ?Sized> {
+    r: &'access T,
+    id_imprint: ID::Imprint,
+    _alloc_lt: PhantomData<&'alloc T>,
+}
+
+impl<'alloc, 'access, ID: EFID, T: 'static> EFVal<'alloc, 'access, ID, T> {
+    pub fn id_imprint(&self) -> ID::Imprint {
+        self.id_imprint
+    }
+
+    pub fn as_ref(&self) -> EFRef<'alloc, ID, T> {
+        EFRef {
+            r: unsafe { &*(self.r as *const _ as *const UnsafeCell<MaybeUninit<T>>) },
+            id_imprint: self.id_imprint,
+        }
+    }
+
+    pub fn as_mut(&self) -> EFMutRef<'alloc, ID, T> {
+        EFMutRef {
+            r: unsafe { &*(self.r as *const _ as *const UnsafeCell<MaybeUninit<T>>) },
+            id_imprint: self.id_imprint,
+        }
+    }
+}
+
+impl<'alloc, 'access, ID: EFID, T: 'static + ?Sized> Deref for EFVal<'alloc, 'access, ID, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.r
+    }
+}
+
+impl<'alloc, 'access, ID: EFID, T: 'static> Clone for EFVal<'alloc, 'access, ID, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'alloc, 'access, ID: EFID, T: 'static> Copy for EFVal<'alloc, 'access, ID, T> {}
+
+impl<'alloc, 'access, const N: usize, ID: EFID, T> EFVal<'alloc, 'access, ID, [T; N]> {
+    pub fn as_array(&self) -> &[EFVal<'alloc, 'access, ID, T>; N] {
+        unsafe {
+            core::mem::transmute::<
+                &EFVal<'alloc, 'access, ID, [T; N]>,
+                &[EFVal<'alloc, 'access, ID, T>; N],
+            >(&self)
+        }
+    }
+}
+
+pub struct EFSliceVal<'alloc, 'access, ID: EFID, T: 'static> {
+    r: &'access [MaybeUninit<T>],
+    _id_imprint: ID::Imprint,
+    _alloc_lt: PhantomData<&'alloc [T]>,
+}
+
+impl<'alloc, 'access, ID: EFID, T: EFType + 'static> Deref for EFSliceVal<'alloc, 'access, ID, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::mem::transmute::<&[MaybeUninit<T>], &[T]>(&self.r) }
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+// A reference which is validated to be contained in (im)mutably-accessible
+// memory, but which may not be well-aligned for `T`. This is useful for
+// accessing wire formats and `#[repr(C, packed)]` structures.
+//
+// Rust references must always be well-aligned, so these types hold onto a raw
+// pointer instead, and only support accessing the underlying memory through
+// unaligned, byte-wise copies. Values must be validated on an aligned copy,
+// as `EFType::validate` may assume that its argument is well-aligned.
+pub struct EFUnalignedRef<'alloc, ID: EFID, T: 'static> {
+    ptr: *const T,
+    id_imprint: ID::Imprint,
+    _alloc_lt: PhantomData<&'alloc UnsafeCell<MaybeUninit<T>>>,
+}
+
+impl<'alloc, ID: EFID, T: 'static> Clone for EFUnalignedRef<'alloc, ID, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'alloc, ID: EFID, T: 'static> Copy for EFUnalignedRef<'alloc, ID, T> {}
+
+impl<'alloc, ID: EFID, T: 'static> EFUnalignedRef<'alloc, ID, T> {
+    pub fn id_imprint(&self) -> ID::Imprint {
+        self.id_imprint
+    }
+
+    pub fn as_ptr(&self) -> EFPtr<T> {
+        EFPtr(self.ptr as *mut T)
+    }
+
+    /// # Safety
+    ///
+    /// `byte_offset` must point to a `U` contained in the referenced `T`.
+    pub unsafe fn sub_ref_unchecked<U: 'static>(
+        self,
+        byte_offset: usize,
+    ) -> EFUnalignedRef<'alloc, ID, U> {
+        EFUnalignedRef {
+            ptr: unsafe { self.ptr.byte_add(byte_offset) as *const U },
+            id_imprint: self.id_imprint,
+            _alloc_lt: PhantomData,
+        }
+    }
+
+    pub fn copy(&self, access_scope: &AccessScope<ID>) -> EFCopy<T> {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_check_initialized(self.ptr as *const (), core::mem::size_of::<T>());
+
+        // Safety: taking &AccessScope<ID> ensures that no mutable accessible
+        // references into foreign memory exist, and that no foreign code is
+        // accessing this memory. The existance of this type ensures that this
+        // memory is accessible. We copy byte-wise, as it may not be
+        // well-aligned:
+        let mut copy = unsafe { EFCopy::<T>::uninit() };
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                self.ptr as *const u8,
+                copy.0.as_mut_ptr() as *mut u8,
+                core::mem::size_of::<T>(),
+            )
+        };
+        copy
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFType + 'static> EFUnalignedRef<'alloc, ID, T> {
+    /// Copy the referenced value into aligned memory, and validate it.
+    pub fn validate(&self, access_scope: &AccessScope<ID>) -> Option<T> {
+        self.copy(access_scope).validate().ok()
+    }
+}
+
+pub struct EFUnalignedMutRef<'alloc, ID: EFID, T: 'static> {
+    ptr: *mut T,
+    id_imprint: ID::Imprint,
+    _alloc_lt: PhantomData<&'alloc UnsafeCell<MaybeUninit<T>>>,
+}
+
+impl<'alloc, ID: EFID, T: 'static> Clone for EFUnalignedMutRef<'alloc, ID, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'alloc, ID: EFID, T: 'static> Copy for EFUnalignedMutRef<'alloc, ID, T> {}
+
+impl<'alloc, ID: EFID, T: 'static> EFUnalignedMutRef<'alloc, ID, T> {
+    pub fn id_imprint(&self) -> ID::Imprint {
+        self.id_imprint
+    }
+
+    pub fn as_ptr(&self) -> EFPtr<T> {
+        EFPtr(self.ptr)
+    }
+
+    pub fn as_immut(&self) -> EFUnalignedRef<'alloc, ID, T> {
+        EFUnalignedRef {
+            ptr: self.ptr,
+            id_imprint: self.id_imprint,
+            _alloc_lt: PhantomData,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `byte_offset` must point to a `U` contained in the referenced `T`.
+    pub unsafe fn sub_ref_unchecked<U: 'static>(
+        self,
+        byte_offset: usize,
+    ) -> EFUnalignedMutRef<'alloc, ID, U> {
+        EFUnalignedMutRef {
+            ptr: unsafe { self.ptr.byte_add(byte_offset) as *mut U },
+            id_imprint: self.id_imprint,
+            _alloc_lt: PhantomData,
+        }
+    }
+
+    pub fn copy(&self, access_scope: &AccessScope<ID>) -> EFCopy<T> {
+        self.as_immut().copy(access_scope)
+    }
+
+    pub fn write(&self, val: T, access_scope: &mut AccessScope<ID>) {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_mark_initialized(self.ptr as *const (), core::mem::size_of::<T>());
+
+        // Safety: taking &mut AccessScope<ID> ensures that no other accessible
+        // references into foreign memory exist, and that no foreign code is
+        // accessing this memory. The existance of this type ensures that this
+        // memory is mutably accessible.
+        unsafe { core::ptr::write_unaligned(self.ptr, val) }
+    }
+
+    pub fn write_copy(&self, copy: &EFCopy<T>, access_scope: &mut AccessScope<ID>) {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
+
+        shadow_mark_initialized(self.ptr as *const (), core::mem::size_of::<T>());
+
+        // Safety: see `write`. We copy byte-wise, as the destination may not
+        // be well-aligned:
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                copy.0.as_ptr() as *const u8,
+                self.ptr as *mut u8,
+                core::mem::size_of::<T>(),
+            )
+        }
+    }
+}
+
+impl<'alloc, ID: EFID, T: EFType + 'static> EFUnalignedMutRef<'alloc, ID, T> {
+    pub fn validate(&self, access_scope: &AccessScope<ID>) -> Option<T> {
+        self.as_immut().validate(access_scope)
+    }
+}
+
+pub struct EFUnalignedSlice<'alloc, ID: EFID, T: 'static> {
+    ptr: *const T,
+    len: usize,
+    id_imprint: ID::Imprint,
+    _alloc_lt: PhantomData<&'alloc [UnsafeCell<MaybeUninit<T>>]>,
+}
+
+impl<'alloc, ID: EFID, T: 'static> Clone for EFUnalignedSlice<'alloc, ID, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'alloc, ID: EFID, T: 'static> Copy for EFUnalignedSlice<'alloc, ID, T> {}
+
+impl<'alloc, ID: EFID, T: 'static> EFUnalignedSlice<'alloc, ID, T> {
+    pub fn id_imprint(&self) -> ID::Imprint {
+        self.id_imprint
+    }
+
+    pub fn as_ptr(&self) -> EFPtr<T> {
+        EFPtr(self.ptr as *mut T)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, idx: usize) -> Option<EFUnalignedRef<'alloc, ID, T>> {
+        if idx < self.len {
+            Some(EFUnalignedRef {
+                ptr: unsafe { self.ptr.add(idx) },
+                id_imprint: self.id_imprint,
+                _alloc_lt: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> EFUnalignedSliceIter<'alloc, ID, T> {
+        EFUnalignedSliceIter {
+            inner: *self,
+            idx: 0,
         }
     }
 }
 
-pub struct EFSliceIter<'alloc, ID: EFID, T: 'static> {
-    inner: EFSlice<'alloc, ID, T>,
+pub struct EFUnalignedSliceIter<'alloc, ID: EFID, T: 'static> {
+    inner: EFUnalignedSlice<'alloc, ID, T>,
     idx: usize,
 }
 
-impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFSliceIter<'alloc, ID, T> {
-    type Item = EFRef<'alloc, ID, T>;
+impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFUnalignedSliceIter<'alloc, ID, T> {
+    type Item = EFUnalignedRef<'alloc, ID, T>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(item) = self.inner.get(self.idx) {
@@ -1100,83 +3005,103 @@ impl<'alloc, ID: EFID, T: 'static> core::iter::Iterator for EFSliceIter<'alloc,
     }
 }
 
-// -----------------------------------------------------------------------------
-
-pub struct EFVal<'alloc, 'access, ID: EFID, T: 'static + ?Sized> {
-    r: &'access T,
+pub struct EFUnalignedMutSlice<'alloc, ID: EFID, T: 'static> {
+    ptr: *mut T,
+    len: usize,
     id_imprint: ID::Imprint,
-    _alloc_lt: PhantomData<&'alloc T>,
+    _alloc_lt: PhantomData<&'alloc [UnsafeCell<MaybeUninit<T>>]>,
 }
 
-impl<'alloc, 'access, ID: EFID, T: 'static> EFVal<'alloc, 'access, ID, T> {
+impl<'alloc, ID: EFID, T: 'static> EFUnalignedMutSlice<'alloc, ID, T> {
     pub fn id_imprint(&self) -> ID::Imprint {
         self.id_imprint
     }
 
-    pub fn as_ref(&self) -> EFRef<'alloc, ID, T> {
-        EFRef {
-            r: unsafe { &*(self.r as *const _ as *const UnsafeCell<MaybeUninit<T>>) },
-            id_imprint: self.id_imprint,
-        }
+    pub fn as_ptr(&self) -> EFPtr<T> {
+        EFPtr(self.ptr)
     }
 
-    pub fn as_mut(&self) -> EFMutRef<'alloc, ID, T> {
-        EFMutRef {
-            r: unsafe { &*(self.r as *const _ as *const UnsafeCell<MaybeUninit<T>>) },
+    pub fn as_immut(&self) -> EFUnalignedSlice<'alloc, ID, T> {
+        EFUnalignedSlice {
+            ptr: self.ptr,
+            len: self.len,
             id_imprint: self.id_imprint,
+            _alloc_lt: PhantomData,
         }
     }
-}
 
-impl<'alloc, 'access, ID: EFID, T: 'static + ?Sized> Deref for EFVal<'alloc, 'access, ID, T> {
-    type Target = T;
-
-    fn deref(&self) -> &Self::Target {
-        &self.r
+    pub fn len(&self) -> usize {
+        self.len
     }
-}
 
-impl<'alloc, 'access, ID: EFID, T: 'static> Clone for EFVal<'alloc, 'access, ID, T> {
-    fn clone(&self) -> Self {
-        *self
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
-}
-
-impl<'alloc, 'access, ID: EFID, T: 'static> Copy for EFVal<'alloc, 'access, ID, T> {}
 
-impl<'alloc, 'access, const N: usize, ID: EFID, T> EFVal<'alloc, 'access, ID, [T; N]> {
-    pub fn as_array(&self) -> &[EFVal<'alloc, 'access, ID, T>; N] {
-        unsafe {
-            core::mem::transmute::<
-                &EFVal<'alloc, 'access, ID, [T; N]>,
-                &[EFVal<'alloc, 'access, ID, T>; N],
-            >(&self)
+    pub fn get(&self, idx: usize) -> Option<EFUnalignedMutRef<'alloc, ID, T>> {
+        if idx < self.len {
+            Some(EFUnalignedMutRef {
+                ptr: unsafe { self.ptr.add(idx) },
+                id_imprint: self.id_imprint,
+                _alloc_lt: PhantomData,
+            })
+        } else {
+            None
         }
     }
-}
 
-pub struct EFSliceVal<'alloc, 'access, ID: EFID, T: 'static> {
-    r: &'access [MaybeUninit<T>],
-    _id_imprint: ID::Imprint,
-    _alloc_lt: PhantomData<&'alloc [T]>,
-}
+    /// Write the elements yielded by `src` into this slice.
+    ///
+    /// Panics if `src` yields fewer elements than the length of this slice,
+    /// after writing the elements it did yield. Excess elements are ignored.
+    pub fn write_from_iter<I: Iterator<Item = T>>(
+        &self,
+        src: I,
+        access_scope: &mut AccessScope<ID>,
+    ) {
+        if self.id_imprint != access_scope.id_imprint() {
+            panic!(
+                "ID mismatch: {:?} vs. {:?}!",
+                self.id_imprint,
+                access_scope.id_imprint()
+            );
+        }
 
-impl<'alloc, 'access, ID: EFID, T: EFType + 'static> Deref for EFSliceVal<'alloc, 'access, ID, T> {
-    type Target = [T];
+        // Safety: taking &mut AccessScope<ID> ensures that no other accessible
+        // references into foreign memory exist, and that no foreign code is
+        // accessing this memory. The existance of this type ensures that this
+        // memory is mutably accessible.
+        let mut count = 0;
+        src.take(self.len).for_each(|val| {
+            unsafe { core::ptr::write_unaligned(self.ptr.add(count), val) };
+            count += 1;
+        });
 
-    fn deref(&self) -> &Self::Target {
-        unsafe { core::mem::transmute::<&[MaybeUninit<T>], &[T]>(&self.r) }
+        // Only mark the elements actually written:
+        shadow_mark_initialized(self.ptr as *const (), core::mem::size_of::<T>() * count);
+        assert!(count == self.len);
     }
 }
 
 mod primitives {
     //! Implementations of [`EFType`] for primitive Rust types.
 
-    use super::EFType;
+    use super::{
+        AllocTracker, EFAsBytes, EFDeepValidationCtx, EFFromBytes, EFType, EFTypeDeep,
+        EFValidationError,
+    };
 
     /// Validating an array requires validation of every element.
     unsafe impl<const N: usize, T: EFType> EFType for [T; N] {
+        const ALWAYS_VALID: bool = T::ALWAYS_VALID;
+
         unsafe fn validate(array: *const Self) -> bool {
+            // Avoid iterating over arrays of unconditionally valid types. This
+            // branch is resolved at compile time:
+            if T::ALWAYS_VALID {
+                return true;
+            }
+
             // The array must have been validated to be well-aligned and
             // accessible. It must further be smaller than or equal to
             // isize::MAX in size, or otherwise the `.add` method invocation may
@@ -1202,8 +3127,62 @@ mod primitives {
             // element, to the entire array is valid:
             true
         }
+
+        unsafe fn validate_detailed(array: *const Self) -> Result<(), EFValidationError> {
+            if T::ALWAYS_VALID {
+                return Ok(());
+            }
+
+            // See the comments in `validate` above.
+            let mut elem = array as *const T;
+
+            for i in 0..N {
+                EFType::validate_detailed(elem)
+                    .map_err(|e| e.within_index(i, i * core::mem::size_of::<T>()))?;
+
+                elem = elem.add(1);
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Deeply validating an array requires deep validation of every element.
+    unsafe impl<const N: usize, T: EFTypeDeep> EFTypeDeep for [T; N] {
+        const ALWAYS_VALID_DEEP: bool = T::ALWAYS_VALID_DEEP;
+
+        unsafe fn validate_deep<R: AllocTracker>(
+            array: *const Self,
+            ctx: &EFDeepValidationCtx<'_, R>,
+        ) -> bool {
+            // Avoid iterating over arrays of unconditionally deeply valid
+            // types. This branch is resolved at compile time:
+            if T::ALWAYS_VALID_DEEP {
+                return true;
+            }
+
+            // See the comments in `EFType::validate` for arrays.
+            let mut elem = array as *const T;
+
+            for _i in 0..N {
+                if !EFTypeDeep::validate_deep(elem, ctx) {
+                    return false;
+                }
+
+                elem = elem.add(1);
+            }
+
+            true
+        }
     }
 
+    /// Arrays can be constructed from bytes, if their elements can.
+    unsafe impl<const N: usize, T: EFFromBytes> EFFromBytes for [T; N] {}
+
+    /// Arrays never introduce padding between their elements, as the size of
+    /// a type is always a multiple of its alignment.
+    unsafe impl<const N: usize, T: EFAsBytes> EFAsBytes for [T; N] {}
+
     macro_rules! unconditionally_valid {
 	// Attempt to try to support generic arguments:
 	// ($( #[ $attrs:tt ] )* for<$( $generics:ty ),*> $( $target:tt )*) => {
@@ -1225,11 +3204,23 @@ mod primitives {
 	    /// can assume it to be valid without reading back its memory.
 	    $( #[ $( $attrs )* ] )*
 	    unsafe impl crate::types::EFType for $target {
+		const ALWAYS_VALID: bool = true;
+
 		unsafe fn validate(_t: *const Self) -> bool {
 		    // Unconditionally valid:
 		    true
 		}
 	    }
+
+	    // Every byte pattern is valid for an unconditionally valid type. All
+	    // types passed to this macro are primitives without padding:
+	    unsafe impl crate::types::EFFromBytes for $target {}
+	    unsafe impl crate::types::EFAsBytes for $target {}
+
+	    // These types do not contain any pointers:
+	    unsafe impl crate::types::EFTypeDeep for $target {
+		const ALWAYS_VALID_DEEP: bool = true;
+	    }
 	}
     }
 
@@ -1238,6 +3229,8 @@ mod primitives {
     /// raw pointers. This does not mean that the resulting pointer is
     /// well-aligned, or safely dereferencable.
     unsafe impl<T> EFType for crate::types::EFPtr<T> {
+        const ALWAYS_VALID: bool = true;
+
         unsafe fn validate(_t: *const Self) -> bool {
             // Well-aligned and accessible pointer values are unconditionally
             // valid:
@@ -1245,8 +3238,27 @@ mod primitives {
         }
     }
 
+    unsafe impl<T> EFFromBytes for crate::types::EFPtr<T> {}
+    unsafe impl<T> EFAsBytes for crate::types::EFPtr<T> {}
+
+    /// A deeply valid [`EFPtr`](crate::types::EFPtr) is either null, or
+    /// points to a deeply valid `T` in memory accessible through the runtime's
+    /// [`AllocTracker`].
+    unsafe impl<T: EFTypeDeep + 'static> EFTypeDeep for crate::types::EFPtr<T> {
+        unsafe fn validate_deep<R: AllocTracker>(
+            t: *const Self,
+            ctx: &EFDeepValidationCtx<'_, R>,
+        ) -> bool {
+            // Read the pointer's numeric value, which is unconditionally
+            // valid, and recurse into its pointee:
+            ctx.validate_ptr(core::ptr::read(t).0 as *const T)
+        }
+    }
+
     /// See the documentation for [`EFPtr as EFType`].
     unsafe impl<T> EFType for *const T {
+        const ALWAYS_VALID: bool = true;
+
         unsafe fn validate(_t: *const Self) -> bool {
             // Well-aligned and accessible pointer values are unconditionally
             // valid:
@@ -1254,8 +3266,20 @@ mod primitives {
         }
     }
 
+    unsafe impl<T> EFFromBytes for *const T {}
+    unsafe impl<T> EFAsBytes for *const T {}
+
+    /// Raw pointers do not carry any assertion about their pointee, and are
+    /// thus not followed. Use [`EFPtr`](crate::types::EFPtr) for pointers
+    /// that should be validated transitively.
+    unsafe impl<T> EFTypeDeep for *const T {
+        const ALWAYS_VALID_DEEP: bool = true;
+    }
+
     /// See the documentation for [`EFPtr as EFType`].
     unsafe impl<T> EFType for *mut T {
+        const ALWAYS_VALID: bool = true;
+
         unsafe fn validate(_t: *const Self) -> bool {
             // Well-aligned and accessible pointer values are unconditionally
             // valid:
@@ -1263,6 +3287,16 @@ mod primitives {
         }
     }
 
+    unsafe impl<T> EFFromBytes for *mut T {}
+    unsafe impl<T> EFAsBytes for *mut T {}
+
+    /// Raw pointers do not carry any assertion about their pointee, and are
+    /// thus not followed. Use [`EFPtr`](crate::types::EFPtr) for pointers
+    /// that should be validated transitively.
+    unsafe impl<T> EFTypeDeep for *mut T {
+        const ALWAYS_VALID_DEEP: bool = true;
+    }
+
     // Implementations for primitives. We would like to implement these on the
     // `std::ffi::c_*` type aliases instead, but those are platform dependent
     // and may produce conflicting implementations. Hence we use Rust's
@@ -1299,7 +3333,21 @@ mod primitives {
             // within the range of valid boolean values:
             core::ptr::read(t as *const u8) < 2
         }
+
+        unsafe fn validate_detailed(t: *const Self) -> Result<(), EFValidationError> {
+            if <Self as EFType>::validate(t) {
+                Ok(())
+            } else {
+                Err(EFValidationError::new_with_bytes(t))
+            }
+        }
     }
+
+    /// Not all byte patterns are valid `bool`s, so this type does not
+    /// implement [`EFFromBytes`]. Every valid `bool` is fully initialized.
+    unsafe impl EFAsBytes for bool {}
+
+    unsafe impl EFTypeDeep for bool {}
 }
 
 /// Get an `EFMutRef` reference to a member of a struct wrapped in an
@@ -1342,3 +3390,550 @@ macro_rules! efmutref_get_field {
         efmutref_get_field_helper($outer_ref)
     }};
 }
+
+#[test]
+fn test_efslice_subslicing() {
+    use crate::branding::EFLifetimeBranding;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let mut buf: [u8; 7] = [0, 1, 2, 3, 4, 5, 6];
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+        let slice: EFSlice<'_, EFLifetimeBranding<'_>, u8> = unsafe {
+            EFPtr::from(&mut buf as *mut [u8; 7] as *mut u8)
+                .upgrade_unchecked_slice(7, brand.get_imprint())
+        };
+
+        let sub = slice.get_range(2..=4).unwrap();
+        assert_eq!(&*sub.validate(&access_scope).unwrap(), &[2, 3, 4]);
+        assert!(slice.get_range(5..8).is_none());
+        assert!(slice
+            .get_range((Bound::Excluded(4), Bound::Excluded(4)))
+            .is_none());
+
+        let (head, tail) = slice.split_at(3).unwrap();
+        assert_eq!(&*head.validate(&access_scope).unwrap(), &[0, 1, 2]);
+        assert_eq!(&*tail.validate(&access_scope).unwrap(), &[3, 4, 5, 6]);
+        assert!(slice.split_at(8).is_none());
+
+        let (first, rest) = slice.split_first().unwrap();
+        assert_eq!(*first.validate(&access_scope).unwrap(), 0);
+        assert_eq!(rest.len(), 6);
+
+        assert_eq!(slice.chunks(3).map(|c| c.len()).sum::<usize>(), 7);
+        let mut chunks_exact = slice.chunks_exact(3);
+        assert_eq!(chunks_exact.by_ref().count(), 2);
+        assert_eq!(
+            &*chunks_exact.remainder().validate(&access_scope).unwrap(),
+            &[6]
+        );
+
+        assert!(slice
+            .windows(2)
+            .map(|w| w.validate(&access_scope).unwrap())
+            .all(|w| w[1] == w[0] + 1));
+    })
+}
+
+#[test]
+fn test_efmutslice_bulk_operations() {
+    use crate::branding::EFLifetimeBranding;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let mut buf: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+        let mut access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+        let slice: EFMutSlice<'_, EFLifetimeBranding<'_>, u8> = unsafe {
+            EFPtr::from(&mut buf as *mut [u8; 8] as *mut u8)
+                .upgrade_unchecked_slice_mut(8, brand.get_imprint())
+        };
+
+        // Overlapping, forward copy:
+        slice.copy_within(0..4, 2, &mut access_scope);
+        assert_eq!(
+            &*slice.as_immut().validate(&access_scope).unwrap(),
+            &[0, 1, 0, 1, 2, 3, 6, 7]
+        );
+
+        // Overlapping copy between two distinct EFSlices:
+        let (head, _) = slice.split_at(6).unwrap();
+        let (_, tail) = slice.split_at(2).unwrap();
+        head.copy_from_efslice(&tail.as_immut(), &mut access_scope);
+        assert_eq!(
+            &*slice.as_immut().validate(&access_scope).unwrap(),
+            &[0, 1, 2, 3, 6, 7, 6, 7]
+        );
+
+        let (lo, hi) = slice.split_at(4).unwrap();
+        lo.swap_with(&hi, &mut access_scope);
+        assert_eq!(
+            &*slice.as_immut().validate(&access_scope).unwrap(),
+            &[6, 7, 6, 7, 0, 1, 2, 3]
+        );
+
+        assert_eq!(&*hi.fill(0xAA, &mut access_scope), &[0xAA; 4]);
+    })
+}
+
+#[test]
+fn test_efslice_search() {
+    use crate::branding::EFLifetimeBranding;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+
+        let mut buf: [u8; 6] = *b"MAGIC\0";
+        let bytes: EFSlice<'_, EFLifetimeBranding<'_>, u8> = unsafe {
+            EFPtr::from(&mut buf as *mut [u8; 6] as *mut u8)
+                .upgrade_unchecked_slice(6, brand.get_imprint())
+        };
+        assert!(bytes.starts_with(b"MAG", &access_scope));
+        assert!(!bytes.starts_with(b"MAGIC\0\0", &access_scope));
+        assert!(bytes.ends_with(b"C\0", &access_scope));
+        assert!(bytes.eq_slice(b"MAGIC\0", &access_scope));
+        assert!(!bytes.eq_slice(b"MAGIC", &access_scope));
+        assert_eq!(bytes.find_byte(0, &access_scope), Some(5));
+        assert_eq!(bytes.find_byte(b'X', &access_scope), None);
+
+        // The second element is not a valid bool, and must never match:
+        let mut bools: [u8; 3] = [0, 2, 1];
+        let bools: EFSlice<'_, EFLifetimeBranding<'_>, bool> = unsafe {
+            EFPtr::from(&mut bools as *mut [u8; 3] as *mut bool)
+                .upgrade_unchecked_slice(3, brand.get_imprint())
+        };
+        assert_eq!(bools.position(|b| *b, &access_scope), Some(2));
+        assert!(!bools.starts_with(&[false, true], &access_scope));
+    })
+}
+
+#[test]
+fn test_efslice_byte_casts() {
+    use crate::branding::EFLifetimeBranding;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+
+        let mut buf: [u32; 3] = [1, 2, 3];
+        let words: EFSlice<'_, EFLifetimeBranding<'_>, u32> = unsafe {
+            EFPtr::from(&mut buf as *mut [u32; 3] as *mut u32)
+                .upgrade_unchecked_slice(3, brand.get_imprint())
+        };
+        let bytes = words.as_bytes();
+        assert_eq!(bytes.len(), 12);
+
+        let (first, rest) = bytes.try_cast_ref_prefix::<u32>().unwrap();
+        assert_eq!(*first.validate(&access_scope).unwrap(), 1);
+        assert_eq!(
+            &*rest
+                .try_cast_slice::<u32>()
+                .unwrap()
+                .validate(&access_scope)
+                .unwrap(),
+            &[2, 3]
+        );
+
+        // Length mismatch, and misaligned:
+        assert!(bytes.try_cast_ref::<u32>().is_none());
+        assert!(bytes
+            .get_range(1..5)
+            .unwrap()
+            .try_cast_ref::<u32>()
+            .is_none());
+        assert!(bytes
+            .get_range(..6)
+            .unwrap()
+            .try_cast_slice::<u32>()
+            .is_none());
+    })
+}
+
+#[test]
+fn test_efunaligned_packed_struct() {
+    use crate::branding::EFLifetimeBranding;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[repr(C, packed)]
+    struct Packed {
+        tag: u8,
+        value: u32,
+    }
+
+    unsafe impl EFType for Packed {
+        unsafe fn validate(_t: *const Self) -> bool {
+            true
+        }
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let mut access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+
+        let mut buf = [0_u8; 1 + 2 * core::mem::size_of::<Packed>()];
+        let packed_ptr = EFPtr::<Packed>::from(unsafe { buf.as_mut_ptr().add(1) } as *mut Packed);
+        let packed =
+            unsafe { packed_ptr.upgrade_unchecked_unaligned_slice_mut(2, brand.get_imprint()) };
+
+        packed.write_from_iter(
+            [
+                Packed {
+                    tag: 1,
+                    value: 0xdeadbeef,
+                },
+                Packed { tag: 2, value: 42 },
+            ]
+            .into_iter(),
+            &mut access_scope,
+        );
+
+        let second = packed.as_immut().get(1).unwrap();
+        assert_eq!(
+            second.validate(&access_scope),
+            Some(Packed { tag: 2, value: 42 })
+        );
+
+        let value: EFUnalignedRef<'_, _, u32> = unsafe { second.sub_ref_unchecked(1) };
+        assert_eq!(value.validate(&access_scope), Some(42));
+        assert_eq!(buf[1..6], [1, 0xef, 0xbe, 0xad, 0xde]);
+    })
+}
+
+#[test]
+fn test_efref_validate_deep_linked_list() {
+    use crate::branding::EFLifetimeBranding;
+
+    #[repr(C)]
+    struct Node {
+        value: u32,
+        next: EFPtr<Node>,
+    }
+
+    unsafe impl EFType for Node {
+        unsafe fn validate(_t: *const Self) -> bool {
+            true
+        }
+    }
+
+    unsafe impl EFTypeDeep for Node {
+        unsafe fn validate_deep<R: AllocTracker>(
+            t: *const Self,
+            ctx: &EFDeepValidationCtx<'_, R>,
+        ) -> bool {
+            EFType::validate(t) && EFTypeDeep::validate_deep(core::ptr::addr_of!((*t).next), ctx)
+        }
+    }
+
+    // Only permit accesses to the `nodes` array below:
+    struct RangeTracker(usize, usize);
+
+    unsafe impl AllocTracker for RangeTracker {
+        fn is_valid(&self, ptr: *const (), len: usize) -> bool {
+            (ptr as usize) >= self.0 && (ptr as usize) + len <= self.1
+        }
+
+        fn is_valid_mut(&self, ptr: *mut (), len: usize) -> bool {
+            self.is_valid(ptr as *const (), len)
+        }
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let mut nodes: [Node; 3] = [
+            Node {
+                value: 0,
+                next: EFPtr::null(),
+            },
+            Node {
+                value: 1,
+                next: EFPtr::null(),
+            },
+            Node {
+                value: 2,
+                next: EFPtr::null(),
+            },
+        ];
+        let base = nodes.as_mut_ptr();
+        let alloc_scope = unsafe {
+            (*base).next = EFPtr::from(base.add(1));
+            (*base.add(1)).next = EFPtr::from(base.add(2));
+
+            AllocScope::<'_, _, EFLifetimeBranding<'_>>::new(
+                RangeTracker(base as usize, base.add(3) as usize),
+                brand.get_imprint(),
+            )
+        };
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+        let head = EFPtr::from(base).upgrade(&alloc_scope).unwrap();
+
+        assert!(head.validate_deep(&alloc_scope, &access_scope, 2).is_some());
+        assert!(head.validate_deep(&alloc_scope, &access_scope, 1).is_none());
+
+        // A cycle back to the head is fine:
+        unsafe { (*base.add(2)).next = EFPtr::from(base) };
+        assert!(head.validate_deep(&alloc_scope, &access_scope, 2).is_some());
+
+        // A pointer outside of tracked memory is not:
+        let mut stray = Node {
+            value: 3,
+            next: EFPtr::null(),
+        };
+        unsafe { (*base.add(2)).next = EFPtr::from(&mut stray as *mut Node) };
+        assert!(head.validate_deep(&alloc_scope, &access_scope, 8).is_none());
+        assert_eq!(stray.value, 3);
+    })
+}
+
+#[test]
+fn test_efref_validate_deep_aliasing_types() {
+    use crate::branding::EFLifetimeBranding;
+
+    #[repr(C)]
+    struct Node {
+        value: u32,
+        flag: EFPtr<bool>,
+        next: EFPtr<Node>,
+    }
+
+    unsafe impl EFType for Node {
+        unsafe fn validate(_t: *const Self) -> bool {
+            true
+        }
+    }
+
+    unsafe impl EFTypeDeep for Node {
+        unsafe fn validate_deep<R: AllocTracker>(
+            t: *const Self,
+            ctx: &EFDeepValidationCtx<'_, R>,
+        ) -> bool {
+            EFType::validate(t)
+                && EFTypeDeep::validate_deep(core::ptr::addr_of!((*t).flag), ctx)
+                && EFTypeDeep::validate_deep(core::ptr::addr_of!((*t).next), ctx)
+        }
+    }
+
+    struct AnyTracker;
+
+    unsafe impl AllocTracker for AnyTracker {
+        fn is_valid(&self, _ptr: *const (), _len: usize) -> bool {
+            true
+        }
+
+        fn is_valid_mut(&self, _ptr: *mut (), _len: usize) -> bool {
+            true
+        }
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let mut node = Node {
+            value: 2,
+            flag: EFPtr::null(),
+            next: EFPtr::null(),
+        };
+        let node_ptr: *mut Node = &mut node;
+        let alloc_scope = unsafe {
+            AllocScope::<'_, _, EFLifetimeBranding<'_>>::new(AnyTracker, brand.get_imprint())
+        };
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+        let node_ref = EFPtr::from(node_ptr).upgrade(&alloc_scope).unwrap();
+
+        // Pointing back to the node itself is a cycle:
+        unsafe { (*node_ptr).next = EFPtr::from(node_ptr) };
+        assert!(node_ref
+            .validate_deep(&alloc_scope, &access_scope, 4)
+            .is_some());
+
+        // A pointer of another type at the same address must be validated
+        // as such. `value` is not a valid `bool`:
+        unsafe { (*node_ptr).flag = EFPtr::from(node_ptr as *mut bool) };
+        assert!(node_ref
+            .validate_deep(&alloc_scope, &access_scope, 4)
+            .is_none());
+
+        unsafe { (*node_ptr).value = 1 };
+        assert!(node_ref
+            .validate_deep(&alloc_scope, &access_scope, 4)
+            .is_some());
+    })
+}
+
+#[test]
+fn test_efref_validate_deep_shared_pointees() {
+    use crate::branding::EFLifetimeBranding;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static VALIDATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    // Every level of this graph points to the next level twice:
+    #[repr(C)]
+    struct Diamond {
+        left: EFPtr<Diamond>,
+        right: EFPtr<Diamond>,
+    }
+
+    unsafe impl EFType for Diamond {
+        unsafe fn validate(_t: *const Self) -> bool {
+            true
+        }
+    }
+
+    unsafe impl EFTypeDeep for Diamond {
+        unsafe fn validate_deep<R: AllocTracker>(
+            t: *const Self,
+            ctx: &EFDeepValidationCtx<'_, R>,
+        ) -> bool {
+            VALIDATIONS.fetch_add(1, Ordering::Relaxed);
+            EFTypeDeep::validate_deep(core::ptr::addr_of!((*t).left), ctx)
+                && EFTypeDeep::validate_deep(core::ptr::addr_of!((*t).right), ctx)
+        }
+    }
+
+    struct AnyTracker;
+
+    unsafe impl AllocTracker for AnyTracker {
+        fn is_valid(&self, _ptr: *const (), _len: usize) -> bool {
+            true
+        }
+
+        fn is_valid_mut(&self, _ptr: *mut (), _len: usize) -> bool {
+            true
+        }
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        const LEVELS: usize = 24;
+        let mut levels: [Diamond; LEVELS] = core::array::from_fn(|_| Diamond {
+            left: EFPtr::null(),
+            right: EFPtr::null(),
+        });
+        let base = levels.as_mut_ptr();
+        for i in 0..LEVELS - 1 {
+            unsafe {
+                (*base.add(i)).left = EFPtr::from(base.add(i + 1));
+                (*base.add(i)).right = EFPtr::from(base.add(i + 1));
+            }
+        }
+
+        let alloc_scope = unsafe {
+            AllocScope::<'_, _, EFLifetimeBranding<'_>>::new(AnyTracker, brand.get_imprint())
+        };
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+        let head = EFPtr::from(base).upgrade(&alloc_scope).unwrap();
+
+        assert!(head
+            .validate_deep(&alloc_scope, &access_scope, LEVELS)
+            .is_some());
+        assert_eq!(VALIDATIONS.load(Ordering::Relaxed), LEVELS);
+    })
+}
+
+#[test]
+fn test_always_valid_skips_validation() {
+    use crate::branding::EFLifetimeBranding;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static VALIDATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    // Counts invocations of its validation function, which always succeeds:
+    #[derive(Clone, Copy)]
+    #[repr(transparent)]
+    struct Counted<const ALWAYS_VALID: bool>(u8);
+
+    unsafe impl<const ALWAYS_VALID: bool> EFType for Counted<ALWAYS_VALID> {
+        const ALWAYS_VALID: bool = ALWAYS_VALID;
+
+        unsafe fn validate(_t: *const Self) -> bool {
+            VALIDATIONS.fetch_add(1, Ordering::Relaxed);
+            true
+        }
+    }
+
+    unsafe impl<const ALWAYS_VALID: bool> EFTypeDeep for Counted<ALWAYS_VALID> {
+        const ALWAYS_VALID_DEEP: bool = ALWAYS_VALID;
+    }
+
+    fn validations<const ALWAYS_VALID: bool>(
+        buf: &mut [Counted<ALWAYS_VALID>; 4],
+        brand: &EFLifetimeBranding<'_>,
+    ) -> usize {
+        struct AnyTracker;
+
+        unsafe impl AllocTracker for AnyTracker {
+            fn is_valid(&self, _ptr: *const (), _len: usize) -> bool {
+                true
+            }
+
+            fn is_valid_mut(&self, _ptr: *mut (), _len: usize) -> bool {
+                true
+            }
+        }
+
+        let alloc_scope = unsafe {
+            AllocScope::<'_, _, EFLifetimeBranding<'_>>::new(AnyTracker, brand.get_imprint())
+        };
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+        let array = EFPtr::from(buf as *mut [Counted<ALWAYS_VALID>; 4])
+            .upgrade(&alloc_scope)
+            .unwrap();
+        let slice = EFPtr::from(buf.as_mut_ptr())
+            .upgrade_slice(4, &alloc_scope)
+            .unwrap();
+
+        VALIDATIONS.store(0, Ordering::Relaxed);
+        assert!(array.validate(&access_scope).is_some());
+        assert!(slice.validate(&access_scope).is_some());
+        assert!(array
+            .validate_deep(&alloc_scope, &access_scope, 1)
+            .is_some());
+        assert!(slice
+            .validate_deep(&alloc_scope, &access_scope, 1)
+            .is_some());
+        VALIDATIONS.load(Ordering::Relaxed)
+    }
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        assert_eq!(validations::<false>(&mut [Counted(0); 4], &brand), 16);
+        assert_eq!(validations::<true>(&mut [Counted(0); 4], &brand), 0);
+    })
+}
+
+#[test]
+fn test_validate_detailed_nested_array() {
+    use crate::branding::EFLifetimeBranding;
+
+    EFLifetimeBranding::new::<()>(|brand| {
+        let access_scope =
+            unsafe { AccessScope::<EFLifetimeBranding<'_>>::new(brand.get_imprint()) };
+
+        let mut buf: [[u8; 2]; 3] = [[0, 1], [1, 0], [1, 2]];
+        let bools: EFSlice<'_, EFLifetimeBranding<'_>, [bool; 2]> = unsafe {
+            EFPtr::from(&mut buf as *mut [[u8; 2]; 3] as *mut [bool; 2])
+                .upgrade_unchecked_slice(3, brand.get_imprint())
+        };
+
+        let err = bools.validate_detailed(&access_scope).err().unwrap();
+        assert_eq!(err.type_name(), "bool");
+        assert_eq!(err.byte_offset(), 5);
+        assert_eq!(err.path(), "[2][1]");
+        assert_eq!(err.bytes(), &[2]);
+
+        let err = err.within_field("flags", 8);
+        assert_eq!(err.byte_offset(), 13);
+        assert_eq!(err.path(), ".flags[2][1]");
+
+        // Values which may contain uninitialized bytes don't retain any:
+        #[repr(C)]
+        struct Padded(u8, u32);
+        let padded = core::mem::MaybeUninit::<Padded>::uninit();
+        assert!(EFValidationError::new(padded.as_ptr()).bytes().is_empty());
+
+        assert!(bools
+            .get_range(..2)
+            .unwrap()
+            .validate_detailed(&access_scope)
+            .is_ok());
+    })
+}